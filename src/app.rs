@@ -1,32 +1,136 @@
-use anyhow::Result;
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, Local, TimeDelta};
 use crossterm::event::{self, Event, KeyCode};
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     prelude::CrosstermBackend,
     style::{Color, Modifier, Style, Stylize},
+    text::Text,
     text::{Line, Span},
     widgets::{
-        Block, Borders, Cell, HighlightSpacing, List, ListItem, ListState, Paragraph, Row, Table,
-        TableState, Wrap,
+        Block, Borders, Cell, Clear, Gauge, HighlightSpacing, List, ListItem, ListState, Paragraph,
+        Row, Table, TableState, Tabs, Wrap,
     },
     Frame, Terminal,
 };
 use redis::{Client, Commands};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::io; // Ensure these imports exist
+use std::time::{Duration, Instant};
+
+use crate::collections::{ListItems, SetMembers, ZSetMembers, ZSetRange};
+use crate::compress::{self, Compression, Decompressor};
+use crate::config::Profile;
+use crate::confirm::{Confirm, ConfirmAction};
+use crate::connection;
+use crate::database::DatabasePicker;
+use crate::decode::{Decoded, DecoderRegistry};
+use crate::delete::BulkDelete;
+use crate::display::{escape_bytes, escape_multiline};
+use crate::editor::{self, EditRequest};
+use crate::form::{self, NewKeyForm};
+use crate::hexdump::{self, BYTES_PER_LINE};
+use crate::json;
+use crate::profiles::ProfilePicker;
+use crate::prompt::{centered_rect, Prompt, PromptKind};
+use crate::scan::KeyScanner;
+use crate::stream::{InfoRows, StreamTab, StreamView};
+use crate::tls::{self, TlsFiles};
+use crate::tree::{KeyTree, TreeRow, DEFAULT_DELIMITER};
+use crate::ttl::{self, Expiry};
+
+// 默认每次SCAN的COUNT
+pub const DEFAULT_SCAN_COUNT: usize = 1000;
+// 默认不自动刷新选中键, 大键的HGETALL等全量读取会阻塞生产实例
+pub const DEFAULT_REFRESH: &str = "off";
+// 没有按键时重绘界面的间隔, TTL倒计时依赖它
+const TICK_RATE: Duration = Duration::from_millis(250);
+// 距离列表末尾多少项时预加载下一页
+const SCAN_PREFETCH_THRESHOLD: usize = 50;
+// 详情表格距离末尾多少行时加载下一个窗口
+const DETAILS_PREFETCH_THRESHOLD: usize = 20;
+// PageUp/PageDown滚动值面板的行数
+const VALUE_PAGE_LINES: usize = 16;
+// 服务端搜索可选的TYPE过滤, None表示不过滤
+const SEARCH_TYPES: [Option<&str>; 7] = [
+    None,
+    Some("string"),
+    Some("hash"),
+    Some("list"),
+    Some("set"),
+    Some("zset"),
+    Some("stream"),
+];
 
 // 应用状态
 pub struct App {
     redis_client: Option<Client>,
     redis_connection: Option<redis::Connection>,
-    keys: Vec<String>,
-    search_match_keys: Vec<String>,
-    key_details: HashMap<String, KeyDetails>,
+    tls_files: TlsFiles,
+    // 当前连接档案的名称和状态栏颜色
+    profile_name: Option<String>,
+    profile_color: Option<Color>,
+    profile_picker: Option<ProfilePicker>,
+    // 在启动界面中选择的档案, 由主循环挂起界面后读取密码并连接
+    connect_request: Option<(String, Profile)>,
+    // 当前连接的数据库编号
+    db: i64,
+    keys: Vec<Vec<u8>>,
+    key_scanner: KeyScanner,
+    scan_count: usize,
+    search_match_keys: Vec<Vec<u8>>,
+    key_details: HashMap<Vec<u8>, KeyDetails>,
     status: String,
     search_query: String,
+    // 按 / 后搜索框获得输入焦点, 大写字母和空格也作为搜索内容
+    search_focused: bool,
+    search_mode: SearchMode,
+    search_type_index: usize,
+    search_scanner: Option<KeyScanner>,
     key_list_state: ListState,
+    key_tree: KeyTree,
+    tree_view: bool,
     key_details_vertical_scroll_state: TableState,
+    value_mode: ValueMode,
+    value_scroll: usize,
+    // 上次渲染的值面板区域, 用于限制滚动范围
+    value_area: Rect,
+    json_collapsed: HashSet<String>,
+    decoders: DecoderRegistry,
+    decompressor: Decompressor,
     select_view: SelectView,
+    prompt: Option<Prompt>,
+    confirm: Option<Confirm>,
+    edit_request: Option<EditRequest>,
+    new_key_form: Option<NewKeyForm>,
+    database_picker: Option<DatabasePicker>,
+    marked_keys: HashSet<Vec<u8>>,
+    unlink: bool,
+    task: Option<Task>,
+    refresh_interval: Option<Duration>,
+    last_refresh: Instant,
+}
+
+// 分多轮执行的任务, 每轮之间刷新界面, Esc取消
+enum Task {
+    // 加载完全部搜索结果后确认删除
+    CollectMatches,
+    Delete(BulkDelete),
+}
+
+// 搜索方式: 本地子串过滤或服务端SCAN MATCH
+#[derive(PartialEq, Eq, Clone, Copy)]
+enum SearchMode {
+    Local,
+    Server,
+}
+
+// 值的显示方式: 自动格式化(如JSON)、原始文本或十六进制转储
+#[derive(PartialEq, Eq, Clone, Copy)]
+enum ValueMode {
+    Pretty,
+    Raw,
+    Hex,
 }
 
 #[derive(PartialEq, Eq)]
@@ -37,14 +141,55 @@ enum SelectView {
 // 键详情结构
 struct KeyDetails {
     key_type: String,
-    ttl: i64,
-    value: String,
-    hash_fields: Option<HashMap<String, String>>,
+    // 剩余毫秒数, -1表示不过期, -2表示键不存在
+    pttl: i64,
+    expires_at: Option<DateTime<Local>>,
+    // 自动刷新发现内容变化的时间
+    changed_at: Option<DateTime<Local>>,
+    value: Vec<u8>,
+    decoded: Option<Decoded>,
+    compression: Option<Compression>,
+    // 按字段名排序, 刷新后表格行的顺序保持不变
+    hash_fields: Option<BTreeMap<Vec<u8>, Vec<u8>>>,
+    list_items: Option<ListItems>,
+    set_members: Option<SetMembers>,
+    zset_members: Option<ZSetMembers>,
+    stream: Option<StreamView>,
+}
+
+impl KeyDetails {
+    // 比较刷新前后的内容, 集合类型只比较新读取的第一页, 不受已加载的后续页影响
+    fn same_content(&self, new: &KeyDetails) -> bool {
+        self.key_type == new.key_type
+            && self.value == new.value
+            && self.hash_fields == new.hash_fields
+            && same_window(&self.list_items, &new.list_items, |old, new| {
+                old.len == new.len && starts_with(&old.items, &new.items)
+            })
+            && same_window(&self.set_members, &new.set_members, |old, new| {
+                old.len == new.len && starts_with(&old.members, &new.members)
+            })
+            && same_window(&self.zset_members, &new.zset_members, |old, new| {
+                old.len == new.len && starts_with(&old.members, &new.members)
+            })
+            && same_window(&self.stream, &new.stream, |old, new| {
+                old.len == new.len && starts_with(&old.entries, &new.entries)
+            })
+    }
 }
 
 impl App {
     pub fn run(&mut self, terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<bool> {
         terminal.draw(|frame| self.render(frame))?;
+        // 任务进行中时不阻塞等待按键, 没有按键就继续处理下一轮
+        if self.task.is_some() && !event::poll(Duration::ZERO)? {
+            self.step_task();
+            return Ok(false);
+        }
+        if !event::poll(TICK_RATE)? {
+            self.on_tick();
+            return Ok(false);
+        }
         if let Event::Key(key) = event::read()? {
             return self.handle_key_events(key.code);
         }
@@ -54,14 +199,44 @@ impl App {
         Self {
             redis_client: None,
             redis_connection: None,
+            tls_files: TlsFiles::default(),
+            profile_name: None,
+            profile_color: None,
+            profile_picker: None,
+            connect_request: None,
+            db: 0,
             keys: Vec::new(),
+            key_scanner: KeyScanner::new(DEFAULT_SCAN_COUNT),
+            scan_count: DEFAULT_SCAN_COUNT,
             search_match_keys: Vec::new(),
             key_details: HashMap::new(),
             status: String::from("Not connected to Redis server"),
             search_query: String::new(),
+            search_focused: false,
+            search_mode: SearchMode::Local,
+            search_type_index: 0,
+            search_scanner: None,
             key_list_state: ListState::default(),
+            key_tree: KeyTree::new(DEFAULT_DELIMITER),
+            tree_view: false,
             key_details_vertical_scroll_state: TableState::default(),
+            value_mode: ValueMode::Pretty,
+            value_scroll: 0,
+            value_area: Rect::default(),
+            json_collapsed: HashSet::new(),
+            decoders: DecoderRegistry::new(),
+            decompressor: Decompressor::default(),
             select_view: SelectView::SelectKeyList,
+            prompt: None,
+            confirm: None,
+            edit_request: None,
+            new_key_form: None,
+            database_picker: None,
+            marked_keys: HashSet::new(),
+            unlink: false,
+            task: None,
+            refresh_interval: None,
+            last_refresh: Instant::now(),
         }
     }
 
@@ -69,85 +244,1343 @@ impl App {
         self.status = status;
     }
 
+    pub fn set_scan_count(&mut self, count: usize) {
+        self.scan_count = count.max(1);
+    }
+
+    pub fn set_decoders(&mut self, decoders: DecoderRegistry) {
+        self.decoders = decoders;
+    }
+
+    pub fn set_tree_delimiter(&mut self, delimiter: &str) {
+        self.key_tree = KeyTree::new(delimiter);
+    }
+
+    pub fn set_refresh_interval(&mut self, interval: Option<Duration>) {
+        self.refresh_interval = interval;
+    }
+
+    pub fn set_unlink(&mut self, unlink: bool) {
+        self.unlink = unlink;
+    }
+
+    pub fn set_profile_picker(&mut self, profiles: Vec<(String, Profile)>) {
+        self.profile_picker = Some(ProfilePicker::new(profiles));
+        self.status = "Select a connection profile".to_string();
+    }
+
+    pub fn take_connect_request(&mut self) -> Option<(String, Profile)> {
+        self.connect_request.take()
+    }
+
+    // 按连接档案连接, 失败时在状态栏显示隐藏了密码的URL
+    pub fn connect(&mut self, name: Option<&str>, profile: &Profile, password: Option<String>) {
+        self.profile_name = name.map(str::to_string);
+        self.profile_color = profile.color().ok().flatten();
+        self.tls_files = TlsFiles {
+            ca_cert: profile.cacert.clone(),
+            cert: profile.cert.clone(),
+            key: profile.key.clone(),
+        };
+        let url = match connection::profile_url(profile, password.as_deref()) {
+            Ok(url) => url,
+            Err(e) => {
+                self.status = format!("Connection failed: {}", e);
+                return;
+            }
+        };
+        match self.connect_redis(&url) {
+            Ok(()) => self.profile_picker = None,
+            Err(e) => {
+                self.status = format!(
+                    "Connection failed: {} URL: {}",
+                    e,
+                    connection::redact_url(&url)
+                )
+            }
+        }
+    }
+
+    pub fn set_decompressor(&mut self, decompressor: Decompressor) {
+        self.decompressor = decompressor;
+    }
+
     // 连接到Redis
     pub fn connect_redis(&mut self, addr: &str) -> Result<()> {
-        let client = Client::open(addr)?;
+        let client = tls::open_client(addr, &self.tls_files)?;
         let conn = client.get_connection()?;
+        self.db = client.get_connection_info().redis.db;
         self.redis_client = Some(client);
         self.redis_connection = Some(conn);
-        self.status = format!("Connect to Redis server: {}", addr);
+        self.status = format!("Connect to Redis server: {}", connection::redact_url(addr));
         self.load_keys()?;
         Ok(())
     }
 
-    // 加载所有键
+    // 重新开始SCAN并加载第一页键
     fn load_keys(&mut self) -> Result<()> {
+        if self.redis_connection.is_none() {
+            return Ok(());
+        }
+        self.keys.clear();
+        self.key_scanner = KeyScanner::new(self.scan_count);
+        self.search_scanner = None;
+        self.key_details.clear();
+        self.marked_keys.clear();
+        self.load_more_keys()?;
+        self.status = format!("Find {} keys", self.keys.len());
+
+        self.select_first_key()
+    }
+
+    // 继续SCAN直到拿到至少一个键或遍历结束
+    // 服务端搜索进行中时加载搜索结果, 否则加载全部键
+    fn load_more_keys(&mut self) -> Result<()> {
         if let Some(conn) = &mut self.redis_connection {
-            let keys: Vec<String> = conn.keys("*")?;
-            self.keys = keys;
-            self.status = format!("Find {} keys", self.keys.len());
-            self.key_details.clear();
+            let (scanner, keys) = match &mut self.search_scanner {
+                Some(scanner) => (scanner, &mut self.search_match_keys),
+                None => (&mut self.key_scanner, &mut self.keys),
+            };
+            let start = keys.len();
+            while !scanner.is_finished() {
+                let page = scanner.next_page(conn)?;
+                if !page.is_empty() {
+                    keys.extend(page);
+                    break;
+                }
+            }
+            // 只过滤新的一页; 收集待删除的键时等结束后再重建树
+            if self.search_scanner.is_none() {
+                self.filter_new_keys(start);
+            }
+            if !matches!(self.task, Some(Task::CollectMatches)) {
+                self.rebuild_key_tree();
+            }
+        }
+        Ok(())
+    }
+
+    // 当前键列表对应的SCAN是否已结束
+    fn scan_finished(&self) -> bool {
+        match &self.search_scanner {
+            Some(scanner) => scanner.is_finished(),
+            None => self.key_scanner.is_finished(),
+        }
+    }
+
+    // 选中项接近列表末尾时加载下一页
+    fn prefetch_keys(&mut self) -> Result<()> {
+        let selected = self.key_list_state.selected().unwrap_or(0);
+        if !self.scan_finished() && selected + SCAN_PREFETCH_THRESHOLD >= self.key_row_count() {
+            self.load_more_keys()?;
+        }
+        Ok(())
+    }
+
+    fn search_type(&self) -> Option<&'static str> {
+        SEARCH_TYPES[self.search_type_index]
+    }
+
+    // 以SCAN MATCH <glob> TYPE <type>在服务端搜索
+    fn server_search(&mut self) -> Result<()> {
+        let pattern = if self.search_query.is_empty() {
+            "*".to_string()
+        } else {
+            self.search_query.clone()
+        };
+        self.search_scanner = Some(
+            KeyScanner::new(self.scan_count)
+                .with_match(&pattern)
+                .with_type(self.search_type()),
+        );
+        self.search_match_keys.clear();
+        self.load_more_keys()?;
+        self.status = format!(
+            "Server search MATCH {} TYPE {}",
+            pattern,
+            self.search_type().unwrap_or("*")
+        );
+        self.select_first_key()
+    }
+
+    // 选中第一个键并加载详情
+    fn select_first_key(&mut self) -> Result<()> {
+        if self.key_row_count() > 0 {
             self.key_list_state.select(Some(0));
+            self.load_selected_details()?;
+        } else {
+            self.key_list_state.select(None);
+        }
+        Ok(())
+    }
+
+    // 获取键详情
+    fn load_key_details(&mut self, key: &[u8]) -> Result<()> {
+        if let Some(details) = self.fetch_key_details(key, None)? {
+            self.key_details.insert(key.to_vec(), details);
+        }
+        Ok(())
+    }
+
+    // 从Redis读取键详情, 提供view时沿用其中集合的过滤条件、范围、排序和页签
+    fn fetch_key_details(
+        &mut self,
+        key: &[u8],
+        view: Option<&KeyDetails>,
+    ) -> Result<Option<KeyDetails>> {
+        let Some(conn) = &mut self.redis_connection else {
+            return Ok(None);
+        };
+        // 获取键类型
+        let key_type: String = redis::cmd("TYPE").arg(key).query(conn)?;
+
+        // 获取毫秒精度的TTL
+        let pttl: i64 = conn.pttl(key)?;
+
+        let mut details = KeyDetails {
+            key_type: key_type.clone(),
+            pttl,
+            expires_at: (pttl >= 0).then(|| Local::now() + TimeDelta::milliseconds(pttl)),
+            changed_at: None,
+            value: Vec::new(),
+            decoded: None,
+            compression: None,
+            hash_fields: None,
+            list_items: None,
+            set_members: None,
+            zset_members: None,
+            stream: None,
+        };
+
+        // 根据类型获取值
+        match key_type.as_str() {
+            "string" => {
+                details.value = conn.get(key)?;
+                // 识别出压缩格式时显示解压后的内容
+                if let Some((compression, data)) = self.decompressor.decompress(key, &details.value)
+                {
+                    details.compression = Some(compression);
+                    details.value = data;
+                }
+                details.decoded = self.decoders.decode(key, &details.value);
+            }
+            "hash" => {
+                let fields: BTreeMap<Vec<u8>, Vec<u8>> = conn.hgetall(key)?;
+                details.value = format!("Hash type, {} fields", fields.len()).into_bytes();
+                details.hash_fields = Some(fields);
+            }
+            "list" => {
+                let list = ListItems::load(conn, key)?;
+                details.value = format!("List type, {} elements", list.len).into_bytes();
+                details.list_items = Some(list);
+            }
+            "set" => {
+                let pattern = view
+                    .and_then(|view| view.set_members.as_ref())
+                    .and_then(|set| set.pattern.clone());
+                let set = SetMembers::load(conn, key, pattern)?;
+                details.value = format!("Set type, {} elements", set.len).into_bytes();
+                details.set_members = Some(set);
+            }
+            "zset" => {
+                let (range, descending) = view
+                    .and_then(|view| view.zset_members.as_ref())
+                    .map_or((ZSetRange::Rank, false), |zset| {
+                        (zset.range.clone(), zset.descending)
+                    });
+                let zset = ZSetMembers::load(conn, key, range, descending)?;
+                details.value = format!("ZSet type, {} elements", zset.len).into_bytes();
+                details.zset_members = Some(zset);
+            }
+            "stream" => {
+                let old = view.and_then(|view| view.stream.as_ref());
+                let mut stream =
+                    StreamView::load(conn, key, old.is_some_and(|old| old.descending))?;
+                if let Some(old) = old {
+                    stream.tab = old.tab;
+                    stream.group_index = old.group_index.min(stream.groups.len().saturating_sub(1));
+                    stream.load_tab(conn, key)?;
+                }
+                details.value = format!("Stream type, {} entries", stream.len).into_bytes();
+                details.stream = Some(stream);
+            }
+            _ => details.value = format!("Unknown type {}", key_type).into_bytes(),
+        }
 
-            self.load_key_details(self.keys.clone()[0].as_str())
-                .unwrap()
+        Ok(Some(details))
+    }
+
+    // 没有按键时定时执行: 到达刷新间隔时重新获取选中的键
+    fn on_tick(&mut self) {
+        let Some(interval) = self.refresh_interval else {
+            return;
+        };
+        // 输入或确认过程中不刷新, 避免内容在操作时变化
+        if self.last_refresh.elapsed() < interval
+            || self.prompt.is_some()
+            || self.confirm.is_some()
+            || self.new_key_form.is_some()
+        {
+            return;
+        }
+        self.last_refresh = Instant::now();
+        if let Err(e) = self.refresh_selected() {
+            self.status = format!("Refresh failed: {}", e);
+        }
+    }
+
+    // 重新获取选中的键, 内容变化时替换详情并记录变化时间, 否则只更新TTL
+    fn refresh_selected(&mut self) -> Result<()> {
+        let Some(key) = self.selected_key() else {
+            return Ok(());
+        };
+        let Some(mut old) = self.key_details.remove(&key) else {
+            return Ok(());
+        };
+        let details = match self.fetch_key_details(&key, Some(&old)) {
+            Ok(Some(mut new)) => {
+                if old.same_content(&new) {
+                    old.pttl = new.pttl;
+                    old.expires_at = new.expires_at;
+                    old
+                } else {
+                    new.changed_at = Some(Local::now());
+                    new
+                }
+            }
+            Ok(None) => old,
+            Err(e) => {
+                self.key_details.insert(key, old);
+                return Err(e);
+            }
+        };
+        self.key_details.insert(key, details);
+        Ok(())
+    }
+
+    // 切换键或重新加载时回到详情顶部
+    fn reset_details_scroll(&mut self) {
+        self.key_details_vertical_scroll_state.select(None);
+        self.value_scroll = 0;
+        self.json_collapsed.clear();
+    }
+
+    // 值面板最多能滚动到的行: 解码树可以把光标移到最后一行, 文本和十六进制转储到最后一屏为止
+    fn max_value_scroll(&self) -> usize {
+        if let Some(json) = self.hash_field_json() {
+            let lines = json::render_lines(&json.value, &self.json_collapsed);
+            return lines.len().saturating_sub(1);
+        }
+        let Some(details) = self
+            .selected_key()
+            .and_then(|key| self.key_details.get(&key))
+        else {
+            return 0;
+        };
+        let field_value;
+        let value = match details.key_type.as_str() {
+            "string" if !self.json_tree_active() => &details.value,
+            "hash" if self.value_mode == ValueMode::Hex => match self.selected_hash_field() {
+                Some((_, value)) => {
+                    field_value = value;
+                    &field_value
+                }
+                None => return 0,
+            },
+            _ => return 0,
+        };
+        let height = self.value_area.height.saturating_sub(2) as usize;
+        let lines = match self.value_mode {
+            ValueMode::Hex => hexdump::line_count(value),
+            _ => Paragraph::new(escape_multiline(value))
+                .wrap(Wrap { trim: true })
+                .line_count(self.value_area.width.saturating_sub(2)),
+        };
+        lines.saturating_sub(height)
+    }
+
+    // 切换哈希字段时值面板回到顶部, 折叠状态只对当前字段有效
+    fn reset_field_value(&mut self) {
+        if self.selected_key_type() == Some("hash") {
+            self.value_scroll = 0;
+            self.json_collapsed.clear();
+        }
+    }
+
+    // 选中的字符串值是否以可折叠的解码树显示
+    fn json_tree_active(&self) -> bool {
+        self.value_mode == ValueMode::Pretty
+            && self
+                .selected_key()
+                .and_then(|key| self.key_details.get(&key))
+                .is_some_and(|details| details.key_type == "string" && details.decoded.is_some())
+    }
+
+    // 折叠或展开JSON树中选中行对应的对象或数组
+    fn toggle_json_collapse(&mut self) {
+        let Some(json) = self
+            .selected_key()
+            .and_then(|key| self.key_details.get(&key))
+            .and_then(|details| details.decoded.as_ref())
+        else {
+            return;
+        };
+        let selected = self
+            .key_details_vertical_scroll_state
+            .selected()
+            .unwrap_or(0);
+        let lines = json::render_lines(&json.value, &self.json_collapsed);
+        if let Some(path) = lines.get(selected).and_then(|line| line.path.clone()) {
+            if !self.json_collapsed.remove(&path) {
+                self.json_collapsed.insert(path);
+            }
+        }
+    }
+
+    // 选中的哈希字段值解码后的树, 以值面板顶行作为折叠光标
+    fn hash_field_json(&self) -> Option<Decoded> {
+        if self.value_mode != ValueMode::Pretty || self.selected_key_type() != Some("hash") {
+            return None;
+        }
+        let key = self.selected_key()?;
+        let (_, value) = self.selected_hash_field()?;
+        self.decoders.decode(&key, &value)
+    }
+
+    // 折叠或展开哈希字段值中光标所在行的对象或数组
+    fn toggle_hash_json_collapse(&mut self) {
+        let Some(json) = self.hash_field_json() else {
+            return;
+        };
+        let lines = json::render_lines(&json.value, &self.json_collapsed);
+        let cursor = self.value_scroll.min(lines.len().saturating_sub(1));
+        if let Some(path) = lines.get(cursor).and_then(|line| line.path.clone()) {
+            if !self.json_collapsed.remove(&path) {
+                self.json_collapsed.insert(path);
+            }
+        }
+        self.value_scroll = cursor;
+    }
+
+    // 当前选中的键, 树视图中选中文件夹时为None
+    fn selected_key(&self) -> Option<Vec<u8>> {
+        let selected = self.key_list_state.selected().unwrap_or(0);
+        if self.tree_view {
+            match self.key_tree.rows().get(selected) {
+                Some(TreeRow::Key { key, .. }) => Some(key.clone()),
+                _ => None,
+            }
+        } else {
+            self.get_keys().get(selected).cloned()
+        }
+    }
+
+    // 加载选中键的详情
+    fn load_selected_details(&mut self) -> Result<()> {
+        if let Some(key) = self.selected_key() {
+            self.load_key_details(&key)?;
+            self.reset_details_scroll();
+        }
+        Ok(())
+    }
+
+    // 键列表的可见行数, 树视图中为展开后的行数
+    fn key_row_count(&self) -> usize {
+        if self.tree_view {
+            self.key_tree.rows().len()
+        } else {
+            self.get_keys().len()
+        }
+    }
+
+    // 按当前键列表重建树视图的行, 并保持选中的键或文件夹不变
+    fn rebuild_key_tree(&mut self) {
+        if !self.tree_view {
+            return;
+        }
+        let selected = self
+            .key_list_state
+            .selected()
+            .and_then(|index| self.key_tree.rows().get(index))
+            .map(|row| match row {
+                TreeRow::Folder { prefix, .. } => (prefix.clone(), true),
+                TreeRow::Key { key, .. } => (key.clone(), false),
+            });
+        // 搜索结果全部展开
+        let searching = self.search_scanner.is_some() || !self.search_query.is_empty();
+        let keys = if searching {
+            &self.search_match_keys
+        } else {
+            &self.keys
+        };
+        self.key_tree.rebuild(keys, searching);
+        if let Some(index) =
+            selected.and_then(|(target, is_folder)| self.key_tree.position(&target, is_folder))
+        {
+            self.key_list_state.select(Some(index));
+        }
+    }
+
+    // 切换平铺列表和命名空间树视图, 保持选中的键
+    fn toggle_tree_view(&mut self) {
+        let selected = self.selected_key();
+        self.tree_view = !self.tree_view;
+        if let Some(key) = &selected {
+            if self.tree_view {
+                self.key_tree.reveal(key);
+            }
+        }
+        self.key_list_state.select(None);
+        self.rebuild_key_tree();
+        let index = selected.and_then(|key| {
+            if self.tree_view {
+                self.key_tree.position(&key, false)
+            } else {
+                self.get_keys().iter().position(|k| *k == key)
+            }
+        });
+        self.key_list_state.select(index);
+    }
+
+    // 树视图中左右键: 展开/折叠文件夹, 或移动到子项/上级文件夹
+    fn fold_key_tree(&mut self, expand: bool) -> Result<()> {
+        let Some(index) = self.key_list_state.selected() else {
+            return Ok(());
+        };
+        match self.key_tree.rows().get(index) {
+            Some(TreeRow::Folder {
+                prefix, expanded, ..
+            }) if *expanded != expand => {
+                let prefix = prefix.clone();
+                self.key_tree.set_expanded(&prefix, expand);
+                self.rebuild_key_tree();
+            }
+            Some(TreeRow::Folder { .. }) if expand => {
+                self.key_list_state.select(Some(index + 1));
+                self.load_selected_details()?;
+            }
+            Some(_) if !expand => {
+                if let Some(parent) = self.key_tree.parent_of(index) {
+                    self.key_list_state.select(Some(parent));
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    // 详情表格滚动接近已加载末尾时加载下一个窗口
+    fn prefetch_details(&mut self) -> Result<()> {
+        let Some(key) = self.selected_key() else {
+            return Ok(());
+        };
+        let selected = self
+            .key_details_vertical_scroll_state
+            .selected()
+            .unwrap_or(0);
+        if let (Some(conn), Some(details)) =
+            (&mut self.redis_connection, self.key_details.get_mut(&key))
+        {
+            if let Some(list) = &mut details.list_items {
+                if !list.is_complete() && selected + DETAILS_PREFETCH_THRESHOLD >= list.items.len()
+                {
+                    list.load_more(conn, &key)?;
+                }
+            }
+            if let Some(set) = &mut details.set_members {
+                if !set.is_complete() && selected + DETAILS_PREFETCH_THRESHOLD >= set.members.len()
+                {
+                    set.load_more(conn, &key)?;
+                }
+            }
+            if let Some(zset) = &mut details.zset_members {
+                if !zset.is_complete()
+                    && selected + DETAILS_PREFETCH_THRESHOLD >= zset.members.len()
+                {
+                    zset.load_more(conn, &key)?;
+                }
+            }
+            if let Some(stream) = &mut details.stream {
+                if stream.tab == StreamTab::Entries
+                    && !stream.is_complete()
+                    && selected + DETAILS_PREFETCH_THRESHOLD >= stream.entries.len()
+                {
+                    stream.load_more(conn, &key)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // 以新的范围或排序方向重新加载有序集合
+    fn reload_zset(&mut self, key: &[u8], range: Option<ZSetRange>) -> Result<()> {
+        let (Some(conn), Some(details)) =
+            (&mut self.redis_connection, self.key_details.get_mut(key))
+        else {
+            return Ok(());
+        };
+        let Some(zset) = &details.zset_members else {
+            return Ok(());
+        };
+        let descending = zset.descending;
+        let range = range.unwrap_or_else(|| zset.range.clone());
+        details.zset_members = Some(ZSetMembers::load(conn, key, range, descending)?);
+        self.reset_details_scroll();
+        Ok(())
+    }
+
+    // 修改选中键的流视图, 如切换页签、消费组或排序
+    fn update_stream(
+        &mut self,
+        update: impl FnOnce(&mut StreamView, &mut redis::Connection, &[u8]) -> Result<()>,
+    ) -> Result<()> {
+        let Some(key) = self.selected_key() else {
+            return Ok(());
+        };
+        if let (Some(conn), Some(stream)) = (
+            &mut self.redis_connection,
+            self.key_details
+                .get_mut(&key)
+                .and_then(|details| details.stream.as_mut()),
+        ) {
+            update(stream, conn, &key)?;
+        }
+        self.reset_details_scroll();
+        Ok(())
+    }
+
+    // 当前选中键的类型
+    fn selected_key_type(&self) -> Option<&str> {
+        let key = self.selected_key()?;
+        self.key_details
+            .get(&key)
+            .map(|details| details.key_type.as_str())
+    }
+
+    // 在外部编辑器中编辑选中的字符串值或哈希字段, 由主循环挂起界面后执行
+    fn request_edit(&mut self) {
+        let Some(key) = self.selected_key() else {
+            return;
+        };
+        let Some(details) = self.key_details.get(&key) else {
+            return;
+        };
+        let (field, value, json) = if details.key_type == "hash" {
+            let Some((field, value)) = self.selected_hash_field() else {
+                return;
+            };
+            let json = self
+                .decoders
+                .decode(&key, &value)
+                .is_some_and(|decoded| decoded.decoder == "json");
+            (Some(field), value, json)
+        } else {
+            let json = details
+                .decoded
+                .as_ref()
+                .is_some_and(|decoded| decoded.decoder == "json");
+            (None, details.value.clone(), json)
+        };
+        if std::str::from_utf8(&value).is_err() {
+            self.status = "Binary value cannot be edited as text".to_string();
+            return;
+        }
+        self.edit_request = Some(EditRequest {
+            key,
+            field,
+            value,
+            suffix: if json { ".json" } else { ".txt" },
+        });
+    }
+
+    pub fn take_edit_request(&mut self) -> Option<EditRequest> {
+        self.edit_request.take()
+    }
+
+    // 编辑器退出后显示差异, 确认后再写回
+    pub fn finish_edit(&mut self, request: EditRequest, edited: Result<Vec<u8>>) {
+        match edited {
+            Err(e) => self.status = format!("Edit failed: {}", e),
+            Ok(value) if value == request.value => self.status = "Value unchanged".to_string(),
+            Ok(value) => {
+                let lines = editor::diff_lines(&request.value, &value);
+                self.confirm = Some(match request.field {
+                    Some(field) => Confirm::new(
+                        ConfirmAction::SetHashField {
+                            key: request.key,
+                            field,
+                            value,
+                        },
+                        "HSET",
+                        lines,
+                    ),
+                    None => Confirm::new(
+                        ConfirmAction::SetString {
+                            key: request.key,
+                            value,
+                        },
+                        "SET KEEPTTL",
+                        lines,
+                    ),
+                });
+            }
+        }
+    }
+
+    fn handle_confirm_key(&mut self, key: KeyCode) -> Result<bool> {
+        let Some(confirm) = &mut self.confirm else {
+            return Ok(false);
+        };
+        match key {
+            KeyCode::Char('Y' | 'y') | KeyCode::Enter => {
+                if let Some(confirm) = self.confirm.take() {
+                    if let Err(e) = self.execute_confirm(confirm.action) {
+                        self.status = format!("Write failed: {}", e);
+                    }
+                }
+            }
+            KeyCode::Char('N' | 'n') | KeyCode::Esc => {
+                self.confirm = None;
+                self.status = "Cancelled".to_string();
+            }
+            KeyCode::Up => confirm.scroll_by(-1),
+            KeyCode::Down => confirm.scroll_by(1),
+            KeyCode::PageUp => confirm.scroll_by(-(VALUE_PAGE_LINES as isize)),
+            KeyCode::PageDown => confirm.scroll_by(VALUE_PAGE_LINES as isize),
+            _ => {}
+        }
+        Ok(false)
+    }
+
+    // 执行已确认的写操作并刷新详情
+    fn execute_confirm(&mut self, action: ConfirmAction) -> Result<()> {
+        let Some(conn) = &mut self.redis_connection else {
+            return Ok(());
+        };
+        match action {
+            ConfirmAction::SetString { key, value } => {
+                // 原值是压缩的则按同样格式压缩后写回
+                let value = match self
+                    .key_details
+                    .get(&key)
+                    .and_then(|details| details.compression.as_ref())
+                {
+                    Some(compression) => compress::compress(compression.codec, &value)?,
+                    None => value,
+                };
+                redis::cmd("SET")
+                    .arg(&key)
+                    .arg(value)
+                    .arg("KEEPTTL")
+                    .query::<()>(conn)?;
+                self.load_key_details(&key)?;
+                self.status = format!("Saved {}", escape_bytes(&key));
+            }
+            ConfirmAction::SetHashField { key, field, value } => {
+                conn.hset::<_, _, _, ()>(&key, &field, value)?;
+                self.reload_hash(&key, Some(&field))?;
+                self.status = format!("Saved field {}", escape_bytes(&field));
+            }
+            ConfirmAction::DeleteHashField { key, field } => {
+                conn.hdel::<_, _, ()>(&key, &field)?;
+                self.reload_hash(&key, None)?;
+                self.status = format!("Deleted field {}", escape_bytes(&field));
+            }
+            ConfirmAction::DeleteKeys { keys } => {
+                self.task = Some(Task::Delete(BulkDelete::new(keys, self.unlink)));
+            }
+            ConfirmAction::RenameKey { key, new_key } => {
+                redis::cmd("RENAME")
+                    .arg(&key)
+                    .arg(&new_key)
+                    .query::<()>(conn)?;
+                self.key_renamed(&key, &new_key)?;
+            }
+        }
+        Ok(())
+    }
+
+    // 执行任务的下一轮, 出错时终止任务
+    fn step_task(&mut self) {
+        let result = match (&mut self.task, &mut self.redis_connection) {
+            (Some(Task::CollectMatches), _) => self.load_more_keys(),
+            (Some(Task::Delete(job)), Some(conn)) => job.step(conn),
+            _ => Ok(()),
+        };
+        if let Err(e) = result {
+            self.cancel_task();
+            self.status = format!("Error: {}", e);
+            return;
+        }
+        match &self.task {
+            Some(Task::CollectMatches) if self.scan_finished() => {
+                self.task = None;
+                self.rebuild_key_tree();
+                self.confirm_delete(self.get_keys().clone());
+            }
+            Some(Task::CollectMatches) => {
+                self.status = format!("Collecting matching keys… {}", self.get_keys().len());
+            }
+            Some(Task::Delete(job)) if job.is_finished() => self.cancel_task(),
+            _ => {}
+        }
+    }
+
+    // 结束任务, 删除任务结束时从列表中移除已处理的键
+    fn cancel_task(&mut self) {
+        match self.task.take() {
+            Some(Task::Delete(job)) => {
+                self.status = format!(
+                    "{} {} of {} keys ({} removed)",
+                    job.command(),
+                    job.done,
+                    job.total(),
+                    job.deleted
+                );
+                let processed: HashSet<Vec<u8>> = job.processed().iter().cloned().collect();
+                self.remove_keys(&processed);
+            }
+            Some(Task::CollectMatches) => {
+                self.rebuild_key_tree();
+                self.status = "Cancelled".to_string();
+            }
+            None => {}
+        }
+    }
+
+    // 从键列表和缓存中移除已删除的键
+    fn remove_keys(&mut self, removed: &HashSet<Vec<u8>>) {
+        self.keys.retain(|key| !removed.contains(key));
+        self.search_match_keys.retain(|key| !removed.contains(key));
+        for key in removed {
+            self.key_scanner.remove(key);
+            if let Some(scanner) = &mut self.search_scanner {
+                scanner.remove(key);
+            }
+            self.key_details.remove(key);
+            self.marked_keys.remove(key);
+        }
+        self.rebuild_key_tree();
+        let count = self.key_row_count();
+        let selected = match self.key_list_state.selected() {
+            _ if count == 0 => None,
+            Some(index) => Some(index.min(count - 1)),
+            None => Some(0),
+        };
+        self.key_list_state.select(selected);
+        if let Err(e) = self.load_selected_details() {
+            self.status = format!("Error: {}", e);
+        }
+    }
+
+    // 弹出删除确认, 列出前若干个键
+    fn confirm_delete(&mut self, keys: Vec<Vec<u8>>) {
+        if keys.is_empty() {
+            self.status = "No keys to delete".to_string();
+            return;
+        }
+        const PREVIEW_KEYS: usize = 20;
+        let command = if self.unlink { "UNLINK" } else { "DEL" };
+        let mut lines = vec![Line::from(format!("{} {} key(s):", command, keys.len()))];
+        lines.extend(
+            keys.iter()
+                .take(PREVIEW_KEYS)
+                .map(|key| Line::from(format!("  {}", escape_bytes(key)))),
+        );
+        if keys.len() > PREVIEW_KEYS {
+            lines.push(Line::from(format!(
+                "  … and {} more",
+                keys.len() - PREVIEW_KEYS
+            )));
+        }
+        self.confirm = Some(Confirm::new(
+            ConfirmAction::DeleteKeys { keys },
+            "Delete keys",
+            lines,
+        ));
+    }
+
+    fn handle_form_key(&mut self, key: KeyCode) -> Result<bool> {
+        let Some(form) = &mut self.new_key_form else {
+            return Ok(false);
+        };
+        match key {
+            KeyCode::Esc => self.new_key_form = None,
+            KeyCode::Enter => {
+                if let Some(form) = self.new_key_form.take() {
+                    if let Err(e) = self.create_key(&form) {
+                        self.status = format!("Error: {}", e);
+                        self.new_key_form = Some(form);
+                    }
+                }
+            }
+            _ => form.input(key),
+        }
+        Ok(false)
+    }
+
+    // 按表单创建键并设置过期时间, 键已存在时报错
+    fn create_key(&mut self, form: &NewKeyForm) -> Result<()> {
+        let Some(conn) = &mut self.redis_connection else {
+            return Ok(());
+        };
+        let cmd = form.create_command()?;
+        let expiry = form.expiry()?;
+        if conn.exists(&form.name)? {
+            bail!("Key {} already exists", form.name);
+        }
+        let mut pipe = redis::pipe();
+        pipe.atomic().add_command(cmd).ignore();
+        if let Some(expiry) = expiry {
+            pipe.add_command(expiry.command(form.name.as_bytes()))
+                .ignore();
+        }
+        pipe.query::<()>(conn)?;
+
+        let key = form.name.as_bytes().to_vec();
+        if self.key_scanner.insert(&key) {
+            self.keys.push(key.clone());
+        }
+        self.select_key(&key)?;
+        self.status = format!("Created {} {}", form.key_type(), form.name);
+        Ok(())
+    }
+
+    fn handle_profile_key(&mut self, key: KeyCode) -> Result<bool> {
+        let Some(picker) = &mut self.profile_picker else {
+            return Ok(false);
+        };
+        match key {
+            KeyCode::Esc | KeyCode::Char('Q' | 'q') => return Ok(true),
+            KeyCode::Up => picker.select_previous(),
+            KeyCode::Down => picker.select_next(),
+            // 连接成功后才关闭选择框, 失败时可以改选其它档案
+            KeyCode::Enter => self.connect_request = picker.selected(),
+            _ => {}
+        }
+        Ok(false)
+    }
+
+    fn handle_database_key(&mut self, key: KeyCode) -> Result<bool> {
+        let Some(picker) = &mut self.database_picker else {
+            return Ok(false);
+        };
+        match key {
+            KeyCode::Esc => self.database_picker = None,
+            KeyCode::Up => picker.select_previous(),
+            KeyCode::Down => picker.select_next(),
+            KeyCode::Enter => {
+                if let Some(db) = picker.selected() {
+                    self.database_picker = None;
+                    if let Err(e) = self.select_database(db) {
+                        self.status = format!("Error: {}", e);
+                    }
+                }
+            }
+            _ => {}
+        }
+        Ok(false)
+    }
+
+    // 切换当前连接的数据库并重新加载键列表
+    fn select_database(&mut self, db: i64) -> Result<()> {
+        let Some(conn) = &mut self.redis_connection else {
+            return Ok(());
+        };
+        redis::cmd("SELECT").arg(db).query::<()>(conn)?;
+        self.db = db;
+        self.search_query.clear();
+        self.load_keys()
+    }
+
+    // 用RENAMENX重命名, 目标键已存在时弹出覆盖确认
+    fn rename_key(&mut self, key: &[u8], new_key: &[u8]) -> Result<()> {
+        let Some(conn) = &mut self.redis_connection else {
+            return Ok(());
+        };
+        let renamed: bool = redis::cmd("RENAMENX").arg(key).arg(new_key).query(conn)?;
+        if renamed {
+            return self.key_renamed(key, new_key);
+        }
+        let lines = vec![
+            Line::from(format!("Key {} already exists.", escape_bytes(new_key))),
+            Line::from(format!(
+                "RENAME {} {} (overwrites it)",
+                escape_bytes(key),
+                escape_bytes(new_key)
+            )),
+        ];
+        self.confirm = Some(Confirm::new(
+            ConfirmAction::RenameKey {
+                key: key.to_vec(),
+                new_key: new_key.to_vec(),
+            },
+            "Overwrite key",
+            lines,
+        ));
+        Ok(())
+    }
+
+    // 重命名后就地替换列表、缓存和标记中的键名, 不重新SCAN
+    fn key_renamed(&mut self, key: &[u8], new_key: &[u8]) -> Result<()> {
+        let lists = [
+            (&mut self.keys, Some(&mut self.key_scanner)),
+            (&mut self.search_match_keys, self.search_scanner.as_mut()),
+        ];
+        for (keys, scanner) in lists {
+            keys.retain(|k| k != new_key);
+            let replaced = match keys.iter_mut().find(|k| *k == key) {
+                Some(k) => {
+                    *k = new_key.to_vec();
+                    true
+                }
+                None => false,
+            };
+            // 只有列表中已有新键名时才记为已见, 否则等SCAN返回
+            if let Some(scanner) = scanner {
+                scanner.remove(key);
+                if replaced {
+                    scanner.insert(new_key);
+                } else {
+                    scanner.remove(new_key);
+                }
+            }
+        }
+        self.key_details.remove(new_key);
+        if let Some(details) = self.key_details.remove(key) {
+            self.key_details.insert(new_key.to_vec(), details);
+        }
+        self.marked_keys.remove(new_key);
+        if self.marked_keys.remove(key) {
+            self.marked_keys.insert(new_key.to_vec());
+        }
+        self.select_key(new_key)?;
+        self.status = format!("Renamed {} to {}", escape_bytes(key), escape_bytes(new_key));
+        Ok(())
+    }
+
+    // 复制键, 参数格式同COPY命令: <destination> [DB n] [REPLACE]
+    fn copy_key(&mut self, key: &[u8], input: &str) -> Result<()> {
+        let Some(conn) = &mut self.redis_connection else {
+            return Ok(());
+        };
+        let args = form::split_args(input)?;
+        let Some((destination, options)) = args.split_first() else {
+            bail!("Destination key cannot be empty");
+        };
+        let mut cmd = redis::cmd("COPY");
+        cmd.arg(key).arg(destination);
+        let mut db = self.db;
+        let mut options = options.iter();
+        while let Some(option) = options.next() {
+            match option.to_uppercase().as_str() {
+                "REPLACE" => {
+                    cmd.arg("REPLACE");
+                }
+                "DB" => {
+                    db = options
+                        .next()
+                        .and_then(|db| db.parse().ok())
+                        .ok_or_else(|| anyhow!("Expected a database number after DB"))?;
+                    cmd.arg("DB").arg(db);
+                }
+                _ => bail!("Unknown COPY option \"{}\"", option),
+            }
+        }
+        let copied: bool = cmd.query(conn)?;
+        if !copied {
+            self.status = format!(
+                "Key {} already exists in db {}, add REPLACE to overwrite",
+                destination, db
+            );
+            return Ok(());
+        }
+        if db != self.db {
+            self.status = format!(
+                "Copied {} to {} in db {}",
+                escape_bytes(key),
+                destination,
+                db
+            );
+            return Ok(());
+        }
+        let destination = destination.as_bytes().to_vec();
+        self.key_details.remove(&destination);
+        if self.key_scanner.insert(&destination) {
+            self.keys.push(destination.clone());
+        }
+        self.select_key(&destination)?;
+        self.status = format!(
+            "Copied {} to {}",
+            escape_bytes(key),
+            escape_bytes(&destination)
+        );
+        Ok(())
+    }
+
+    // 把键移动到另一个数据库, 成功后从当前列表中移除
+    fn move_key(&mut self, key: &[u8], input: &str) -> Result<()> {
+        let Some(conn) = &mut self.redis_connection else {
+            return Ok(());
+        };
+        let db: i64 = input
+            .trim()
+            .parse()
+            .map_err(|_| anyhow!("Invalid database number \"{}\"", input))?;
+        if db == self.db {
+            bail!("Key is already in db {}", db);
+        }
+        let moved: bool = redis::cmd("MOVE").arg(key).arg(db).query(conn)?;
+        if !moved {
+            self.status = format!("Key {} already exists in db {}", escape_bytes(key), db);
+            return Ok(());
+        }
+        self.remove_keys(&HashSet::from([key.to_vec()]));
+        self.status = format!("Moved {} to db {}", escape_bytes(key), db);
+        Ok(())
+    }
+
+    // 在键列表中选中指定的键, 被搜索条件过滤掉时清除搜索
+    fn select_key(&mut self, key: &[u8]) -> Result<()> {
+        self.filtered_keys();
+        if !self.get_keys().iter().any(|k| k == key) {
+            self.search_query.clear();
+            self.search_scanner = None;
+            self.filtered_keys();
+        }
+        let index = if self.tree_view {
+            self.key_tree.reveal(key);
+            self.rebuild_key_tree();
+            self.key_tree.position(key, false)
+        } else {
+            self.get_keys().iter().position(|k| k == key)
+        };
+        self.key_list_state.select(index);
+        self.load_selected_details()
+    }
+
+    // 树视图中文件夹下的全部键
+    fn folder_keys(&self, prefix: &[u8]) -> Vec<Vec<u8>> {
+        self.get_keys()
+            .iter()
+            .filter(|key| key.starts_with(prefix))
+            .cloned()
+            .collect()
+    }
+
+    // 选中行对应的键, 树视图中选中文件夹时为其下的全部键
+    fn selected_row_keys(&self) -> Vec<Vec<u8>> {
+        let selected = self.key_list_state.selected().unwrap_or(0);
+        match self.key_tree.rows().get(selected) {
+            Some(TreeRow::Folder { prefix, .. }) if self.tree_view => self.folder_keys(prefix),
+            _ => self.selected_key().into_iter().collect(),
+        }
+    }
+
+    // 标记或取消标记选中的键, 文件夹下的键全部标记时取消标记
+    fn toggle_mark(&mut self) {
+        let keys = self.selected_row_keys();
+        if keys.iter().all(|key| self.marked_keys.contains(key)) {
+            for key in &keys {
+                self.marked_keys.remove(key);
+            }
+        } else {
+            self.marked_keys.extend(keys);
+        }
+        self.status = format!("{} keys marked", self.marked_keys.len());
+    }
+
+    // 当前选中的哈希字段及其值
+    fn selected_hash_field(&self) -> Option<(Vec<u8>, Vec<u8>)> {
+        let key = self.selected_key()?;
+        let index = self.key_details_vertical_scroll_state.selected()?;
+        self.key_details
+            .get(&key)?
+            .hash_fields
+            .as_ref()?
+            .iter()
+            .nth(index)
+            .map(|(field, value)| (field.clone(), value.clone()))
+    }
+
+    // 写入后从Redis重新加载哈希, 并选中指定字段或保持原来的行
+    fn reload_hash(&mut self, key: &[u8], field: Option<&[u8]>) -> Result<()> {
+        let selected = self.key_details_vertical_scroll_state.selected();
+        self.load_key_details(key)?;
+        let fields = self
+            .key_details
+            .get(key)
+            .and_then(|details| details.hash_fields.as_ref());
+        let index = match (fields, field) {
+            (Some(fields), Some(field)) => fields.keys().position(|f| f == field),
+            (Some(fields), None) if !fields.is_empty() => {
+                selected.map(|index| index.min(fields.len() - 1))
+            }
+            _ => None,
+        };
+        self.key_details_vertical_scroll_state.select(index);
+        Ok(())
+    }
+
+    // 重命名字段: 在事务中写入新字段并删除旧字段
+    fn rename_hash_field(&mut self, key: &[u8], field: &[u8], new_field: &[u8]) -> Result<()> {
+        let Some(conn) = &mut self.redis_connection else {
+            return Ok(());
+        };
+        if conn.hexists(key, new_field)? {
+            self.status = format!("Field {} already exists", escape_bytes(new_field));
+            return Ok(());
+        }
+        let Some(value): Option<Vec<u8>> = conn.hget(key, field)? else {
+            self.status = format!("Field {} no longer exists", escape_bytes(field));
+            return Ok(());
+        };
+        redis::pipe()
+            .atomic()
+            .hset(key, new_field, value)
+            .ignore()
+            .hdel(key, field)
+            .ignore()
+            .query::<()>(conn)?;
+        self.reload_hash(key, Some(new_field))?;
+        self.status = format!(
+            "Renamed field {} to {}",
+            escape_bytes(field),
+            escape_bytes(new_field)
+        );
+        Ok(())
+    }
+
+    // 输入框打开时的按键处理
+    fn handle_prompt_key(&mut self, key: KeyCode) -> Result<bool> {
+        let Some(prompt) = &mut self.prompt else {
+            return Ok(false);
+        };
+        match key {
+            KeyCode::Esc => self.prompt = None,
+            KeyCode::Backspace => {
+                prompt.input.pop();
+            }
+            KeyCode::Char(c) => prompt.input.push(c),
+            KeyCode::Enter => {
+                if let Some(prompt) = self.prompt.take() {
+                    if let Err(e) = self.submit_prompt(prompt.kind, prompt.input) {
+                        self.status = format!("Error: {}", e);
+                    }
+                }
+            }
+            _ => {}
         }
-        Ok(())
+        Ok(false)
     }
 
-    // 获取键详情
-    fn load_key_details(&mut self, key: &str) -> Result<()> {
-        if let Some(conn) = &mut self.redis_connection {
-            // 获取键类型
-            let key_type: String = redis::cmd("TYPE").arg(key).query(conn)?;
-
-            // 获取TTL
-            let ttl: i64 = conn.ttl(key)?;
-
-            // 根据类型获取值
-            let (value, hash_fields) = match key_type.as_str() {
-                "string" => {
-                    let value: String = conn.get(key)?;
-                    (value, None)
-                }
-                "hash" => {
-                    let fields: HashMap<String, String> = conn.hgetall(key)?;
-                    let value = format!("Hash type, {} fields", fields.len());
-                    (value, Some(fields))
-                }
-                "list" => {
-                    let len: usize = conn.llen(key)?;
-                    let value = format!("List type, {} elements", len);
-                    (value, None)
-                }
-                "set" => {
-                    let len: usize = conn.scard(key)?;
-                    let value = format!("Set type, {} elements", len);
-                    (value, None)
-                }
-                "zset" => {
-                    let len: usize = conn.zcard(key)?;
-                    let value = format!("ZSet type, {} elements", len);
-                    (value, None)
-                }
-                _ => (String::from(format!("Unknown type {}", key_type)), None),
-            };
-
-            self.key_details.insert(
-                key.to_string(),
-                KeyDetails {
-                    key_type: key_type.clone(),
-                    ttl,
-                    value,
-                    hash_fields,
-                },
-            );
+    // 提交输入框内容
+    fn submit_prompt(&mut self, kind: PromptKind, input: String) -> Result<()> {
+        let Some(key) = self.selected_key() else {
+            return Ok(());
+        };
+        let Some(conn) = &mut self.redis_connection else {
+            return Ok(());
+        };
+        match kind {
+            PromptKind::SetFilter => {
+                let pattern = if input.is_empty() { None } else { Some(input) };
+                let set = SetMembers::load(conn, &key, pattern)?;
+                if let Some(details) = self.key_details.get_mut(&key) {
+                    details.set_members = Some(set);
+                }
+                self.reset_details_scroll();
+            }
+            PromptKind::ZSetScoreRange | PromptKind::ZSetLexRange => {
+                let range = match input.split_whitespace().collect::<Vec<_>>()[..] {
+                    [] => ZSetRange::Rank,
+                    [min, max] => {
+                        let (min, max) = (min.to_string(), max.to_string());
+                        if kind == PromptKind::ZSetScoreRange {
+                            ZSetRange::Score { min, max }
+                        } else {
+                            ZSetRange::Lex { min, max }
+                        }
+                    }
+                    _ => {
+                        self.status = "Expected range: <min> <max>".to_string();
+                        return Ok(());
+                    }
+                };
+                self.reload_zset(&key, Some(range))?;
+            }
+            PromptKind::HexJump => match hexdump::parse_offset(&input) {
+                Some(offset) => {
+                    self.value_mode = ValueMode::Hex;
+                    self.value_scroll = (offset / BYTES_PER_LINE).min(self.max_value_scroll());
+                }
+                None => self.status = format!("Invalid offset: {}", input),
+            },
+            PromptKind::Expire => {
+                let expiry = Expiry::parse(&input)?;
+                let applied: bool = expiry.command(&key).query(conn)?;
+                self.load_key_details(&key)?;
+                self.status = match (applied, expiry) {
+                    (false, Expiry::Persist) => "Key has no expiry".to_string(),
+                    (false, _) => "Key does not exist".to_string(),
+                    (true, Expiry::Persist) => format!("Persisted {}", escape_bytes(&key)),
+                    (true, _) => format!("Expiry set on {}", escape_bytes(&key)),
+                };
+            }
+            PromptKind::HashNewField => {
+                if input.is_empty() {
+                    self.status = "Field name cannot be empty".to_string();
+                    return Ok(());
+                }
+                let title = format!("HSET {} <value>", input);
+                self.prompt = Some(Prompt::new(
+                    PromptKind::HashFieldValue {
+                        field: input.into_bytes(),
+                    },
+                    &title,
+                ));
+            }
+            PromptKind::HashFieldValue { field } => {
+                conn.hset::<_, _, _, ()>(&key, &field, input)?;
+                self.reload_hash(&key, Some(&field))?;
+                self.status = format!("Saved field {}", escape_bytes(&field));
+            }
+            PromptKind::HashRenameField { field } => {
+                if input.is_empty() {
+                    self.status = "Field name cannot be empty".to_string();
+                    return Ok(());
+                }
+                if input.as_bytes() != field {
+                    self.rename_hash_field(&key, &field, input.as_bytes())?;
+                }
+            }
+            PromptKind::RenameKey => {
+                if input.is_empty() {
+                    self.status = "Key name cannot be empty".to_string();
+                    return Ok(());
+                }
+                if input.as_bytes() != key {
+                    self.rename_key(&key, input.as_bytes())?;
+                }
+            }
+            PromptKind::CopyKey => self.copy_key(&key, &input)?,
+            PromptKind::MoveKey => self.move_key(&key, &input)?,
+            PromptKind::SetIsMember => {
+                let is_member: bool = conn.sismember(&key, &input)?;
+                self.status = if is_member {
+                    format!("\"{}\" is a member of {}", input, escape_bytes(&key))
+                } else {
+                    format!("\"{}\" is not a member of {}", input, escape_bytes(&key))
+                };
+            }
         }
         Ok(())
     }
 
-    fn get_keys(&self) -> &Vec<String> {
-        if self.search_query.is_empty() {
+    fn get_keys(&self) -> &Vec<Vec<u8>> {
+        if self.search_scanner.is_some() {
+            &self.search_match_keys
+        } else if self.search_query.is_empty() {
             &self.keys
         } else {
             &self.search_match_keys
@@ -156,6 +1589,39 @@ impl App {
 
     // 处理按键事件
     fn handle_key_events(&mut self, key: KeyCode) -> Result<bool> {
+        if self.task.is_some() {
+            if key == KeyCode::Esc {
+                self.cancel_task();
+            }
+            return Ok(false);
+        }
+        if self.profile_picker.is_some() {
+            return self.handle_profile_key(key);
+        }
+        if self.prompt.is_some() {
+            return self.handle_prompt_key(key);
+        }
+        if self.confirm.is_some() {
+            return self.handle_confirm_key(key);
+        }
+        if self.new_key_form.is_some() {
+            return self.handle_form_key(key);
+        }
+        if self.database_picker.is_some() {
+            return self.handle_database_key(key);
+        }
+        if self.search_focused && self.select_view == SelectView::SelectKeyList {
+            match key {
+                KeyCode::Char(c) => return self.push_search(c),
+                KeyCode::Backspace => return self.pop_search(),
+                KeyCode::Esc => {
+                    self.search_focused = false;
+                    return Ok(false);
+                }
+                // 其余按键离开搜索框后照常处理, Enter在服务端模式下提交搜索
+                _ => self.search_focused = false,
+            }
+        }
         self.status = format!("Press Key: {:?}", key);
         match key {
             KeyCode::Char('Q') | KeyCode::Esc => return Ok(true),
@@ -170,98 +1636,396 @@ impl App {
                 self.load_keys()?;
                 self.status = "Keys list refreshed".to_string();
             }
-            KeyCode::Enter => {}
+            KeyCode::Char('B') => {
+                if let Some(conn) = &mut self.redis_connection {
+                    self.database_picker = Some(DatabasePicker::load(conn, self.db)?);
+                }
+            }
+            KeyCode::Char('M') => {
+                self.search_mode = match self.search_mode {
+                    SearchMode::Local => SearchMode::Server,
+                    SearchMode::Server => SearchMode::Local,
+                };
+                self.search_scanner = None;
+                self.filtered_keys();
+                self.key_list_state.select(None);
+            }
+            KeyCode::Char('V') if self.select_view == SelectView::SelectKeyList => {
+                self.toggle_tree_view();
+            }
+            KeyCode::Left | KeyCode::Right
+                if self.select_view == SelectView::SelectKeyList && self.tree_view =>
+            {
+                self.fold_key_tree(key == KeyCode::Right)?;
+            }
+            KeyCode::Char('T') if self.select_view == SelectView::SelectKeyDetails => {
+                self.prompt = Some(Prompt::new(
+                    PromptKind::Expire,
+                    "Expire in 1h30m / at @unix or 2024-01-02 15:04 (empty: PERSIST)",
+                ));
+            }
+            KeyCode::Char('T') if self.search_mode == SearchMode::Server => {
+                self.search_type_index = (self.search_type_index + 1) % SEARCH_TYPES.len();
+            }
+            KeyCode::Enter
+                if self.select_view == SelectView::SelectKeyList
+                    && self.search_mode == SearchMode::Server =>
+            {
+                // 如Redis 6.0以下不支持SCAN TYPE, 放弃本次搜索并显示错误
+                if let Err(e) = self.server_search() {
+                    self.search_scanner = None;
+                    self.rebuild_key_tree();
+                    self.status = format!("Error: {}", e);
+                }
+            }
+            KeyCode::Char('F')
+                if self.select_view == SelectView::SelectKeyDetails
+                    && self.selected_key_type() == Some("set") =>
+            {
+                self.prompt = Some(Prompt::new(PromptKind::SetFilter, "SSCAN MATCH"));
+            }
+            KeyCode::Char('I')
+                if self.select_view == SelectView::SelectKeyDetails
+                    && self.selected_key_type() == Some("set") =>
+            {
+                self.prompt = Some(Prompt::new(PromptKind::SetIsMember, "SISMEMBER"));
+            }
+            KeyCode::Char('O')
+                if self.select_view == SelectView::SelectKeyDetails
+                    && self.selected_key_type() == Some("zset") =>
+            {
+                if let Some(key) = self.selected_key() {
+                    if let Some(zset) = self
+                        .key_details
+                        .get_mut(&key)
+                        .and_then(|details| details.zset_members.as_mut())
+                    {
+                        zset.descending = !zset.descending;
+                    }
+                    self.reload_zset(&key, None)?;
+                }
+            }
+            KeyCode::Char('O')
+                if self.select_view == SelectView::SelectKeyDetails
+                    && self.selected_key_type() == Some("stream") =>
+            {
+                self.update_stream(|stream, conn, key| {
+                    *stream = StreamView::load(conn, key, !stream.descending)?;
+                    Ok(())
+                })?;
+            }
+            KeyCode::Char('G')
+                if self.select_view == SelectView::SelectKeyDetails
+                    && self.selected_key_type() == Some("stream") =>
+            {
+                self.update_stream(|stream, conn, key| {
+                    if !stream.groups.is_empty() {
+                        stream.group_index = (stream.group_index + 1) % stream.groups.len();
+                    }
+                    stream.load_tab(conn, key)
+                })?;
+            }
+            KeyCode::Left | KeyCode::Right
+                if self.select_view == SelectView::SelectKeyDetails
+                    && self.selected_key_type() == Some("stream") =>
+            {
+                let count = StreamTab::ALL.len();
+                let step = if key == KeyCode::Left { count - 1 } else { 1 };
+                self.update_stream(|stream, conn, key| {
+                    let index = (stream.tab.index() + step) % count;
+                    stream.tab = StreamTab::ALL[index];
+                    stream.load_tab(conn, key)
+                })?;
+            }
+            KeyCode::Char('S')
+                if self.select_view == SelectView::SelectKeyDetails
+                    && self.selected_key_type() == Some("zset") =>
+            {
+                self.prompt = Some(Prompt::new(
+                    PromptKind::ZSetScoreRange,
+                    "ZRANGEBYSCORE <min> <max>",
+                ));
+            }
+            KeyCode::Char('L')
+                if self.select_view == SelectView::SelectKeyDetails
+                    && self.selected_key_type() == Some("zset") =>
+            {
+                self.prompt = Some(Prompt::new(
+                    PromptKind::ZSetLexRange,
+                    "ZRANGEBYLEX <min> <max>",
+                ));
+            }
             KeyCode::Tab => {
                 self.select_view = match self.select_view {
                     SelectView::SelectKeyList => SelectView::SelectKeyDetails,
                     SelectView::SelectKeyDetails => SelectView::SelectKeyList,
                 };
             }
+            KeyCode::Char('X')
+                if self.select_view == SelectView::SelectKeyDetails
+                    && matches!(self.selected_key_type(), Some("string" | "hash")) =>
+            {
+                self.value_mode = match self.value_mode {
+                    ValueMode::Hex => ValueMode::Pretty,
+                    _ => ValueMode::Hex,
+                };
+                self.value_scroll = 0;
+            }
+            KeyCode::Char('P')
+                if self.select_view == SelectView::SelectKeyDetails
+                    && matches!(self.selected_key_type(), Some("string" | "hash")) =>
+            {
+                self.value_mode = match self.value_mode {
+                    ValueMode::Pretty => ValueMode::Raw,
+                    _ => ValueMode::Pretty,
+                };
+                self.value_scroll = 0;
+            }
+            KeyCode::Enter
+                if self.select_view == SelectView::SelectKeyDetails && self.json_tree_active() =>
+            {
+                self.toggle_json_collapse();
+            }
+            KeyCode::Enter
+                if self.select_view == SelectView::SelectKeyDetails
+                    && self.hash_field_json().is_some() =>
+            {
+                self.toggle_hash_json_collapse();
+            }
+            KeyCode::Left | KeyCode::Right
+                if self.select_view == SelectView::SelectKeyDetails
+                    && self.hash_field_json().is_some() =>
+            {
+                self.value_scroll = if key == KeyCode::Left {
+                    self.value_scroll.saturating_sub(1)
+                } else {
+                    (self.value_scroll + 1).min(self.max_value_scroll())
+                };
+            }
+            KeyCode::Char('E')
+                if self.select_view == SelectView::SelectKeyDetails
+                    && matches!(self.selected_key_type(), Some("string" | "hash")) =>
+            {
+                self.request_edit();
+            }
+            KeyCode::Char('A')
+                if self.select_view == SelectView::SelectKeyDetails
+                    && self.selected_key_type() == Some("hash") =>
+            {
+                self.prompt = Some(Prompt::new(PromptKind::HashNewField, "HSET <field>"));
+            }
+            KeyCode::Char('U')
+                if self.select_view == SelectView::SelectKeyDetails
+                    && self.selected_key_type() == Some("hash") =>
+            {
+                if let Some((field, value)) = self.selected_hash_field() {
+                    match String::from_utf8(value) {
+                        Ok(value) => {
+                            let title = format!("HSET {} <value>", escape_bytes(&field));
+                            self.prompt = Some(Prompt::with_input(
+                                PromptKind::HashFieldValue { field },
+                                &title,
+                                &value,
+                            ));
+                        }
+                        Err(_) => self.status = "Binary value cannot be edited as text".to_string(),
+                    }
+                }
+            }
+            KeyCode::Char('N')
+                if self.select_view == SelectView::SelectKeyDetails
+                    && self.selected_key_type() == Some("hash") =>
+            {
+                if let Some((field, _)) = self.selected_hash_field() {
+                    let title = format!("Rename field {}", escape_bytes(&field));
+                    let input = String::from_utf8_lossy(&field).into_owned();
+                    self.prompt = Some(Prompt::with_input(
+                        PromptKind::HashRenameField { field },
+                        &title,
+                        &input,
+                    ));
+                }
+            }
+            KeyCode::Char('D')
+                if self.select_view == SelectView::SelectKeyDetails
+                    && self.selected_key_type() == Some("hash") =>
+            {
+                if let (Some(key), Some((field, _))) =
+                    (self.selected_key(), self.selected_hash_field())
+                {
+                    let lines = vec![Line::from(format!(
+                        "HDEL {} {}",
+                        escape_bytes(&key),
+                        escape_bytes(&field)
+                    ))];
+                    self.confirm = Some(Confirm::new(
+                        ConfirmAction::DeleteHashField { key, field },
+                        "Delete field",
+                        lines,
+                    ));
+                }
+            }
+            KeyCode::Char('J')
+                if self.select_view == SelectView::SelectKeyDetails
+                    && matches!(self.selected_key_type(), Some("string" | "hash")) =>
+            {
+                self.prompt = Some(Prompt::new(PromptKind::HexJump, "Jump to byte offset"));
+            }
+            KeyCode::PageUp if self.select_view == SelectView::SelectKeyDetails => {
+                self.value_scroll = self.value_scroll.saturating_sub(VALUE_PAGE_LINES);
+            }
+            KeyCode::PageDown if self.select_view == SelectView::SelectKeyDetails => {
+                self.value_scroll =
+                    (self.value_scroll + VALUE_PAGE_LINES).min(self.max_value_scroll());
+            }
             KeyCode::Up => match self.select_view {
                 SelectView::SelectKeyDetails => {
-                    self.key_details_vertical_scroll_state.select_previous();
+                    if self.selected_key_type() == Some("string") && !self.json_tree_active() {
+                        self.value_scroll = self.value_scroll.saturating_sub(1);
+                    } else {
+                        self.key_details_vertical_scroll_state.select_previous();
+                        self.reset_field_value();
+                    }
                     return Ok(false);
                 }
                 SelectView::SelectKeyList => {
-                    if !self.keys.is_empty() {
-                        if self.key_list_state.selected().is_some_and(|x| x == 0) {
-                            self.key_list_state.select_last();
-                        } else {
-                            self.key_list_state.select_previous();
-                        }
-                        if let Some(key) = self
-                            .get_keys()
-                            .get(self.key_list_state.selected().unwrap_or(0))
-                        {
-                            self.load_key_details(&key.clone())?;
-                            self.key_details_vertical_scroll_state.select(None);
-                        }
+                    let count = self.key_row_count();
+                    if count > 0 {
+                        let selected = match self.key_list_state.selected() {
+                            Some(x) if x > 0 => (x - 1).min(count - 1),
+                            _ => count - 1,
+                        };
+                        self.key_list_state.select(Some(selected));
+                        self.load_selected_details()?;
                     }
                 }
             },
             KeyCode::Down => match self.select_view {
                 SelectView::SelectKeyDetails => {
-                    self.key_details_vertical_scroll_state.select_next();
+                    if self.selected_key_type() == Some("string") && !self.json_tree_active() {
+                        self.value_scroll = (self.value_scroll + 1).min(self.max_value_scroll());
+                    } else {
+                        self.key_details_vertical_scroll_state.select_next();
+                        self.reset_field_value();
+                        self.prefetch_details()?;
+                    }
                     return Ok(false);
                 }
                 SelectView::SelectKeyList => {
-                    let keys = self.get_keys();
-                    if self
-                        .key_list_state
-                        .selected()
-                        .is_some_and(|x| x == keys.len() - 1)
-                    {
-                        self.key_list_state.select_first();
-                    } else {
-                        self.key_list_state.select_next();
-                    }
-
-                    if let Some(key) = self
-                        .get_keys()
-                        .get(self.key_list_state.selected().unwrap_or(0))
-                    {
-                        self.load_key_details(&key.clone())?;
-                        self.key_details_vertical_scroll_state.select(None);
+                    self.prefetch_keys()?;
+                    let count = self.key_row_count();
+                    if count == 0 {
+                        return Ok(false);
                     }
+                    let selected = match self.key_list_state.selected() {
+                        Some(x) if x + 1 < count => x + 1,
+                        _ => 0,
+                    };
+                    self.key_list_state.select(Some(selected));
+                    self.load_selected_details()?;
                 }
             },
-            KeyCode::Char(c) => match self.select_view {
-                SelectView::SelectKeyList => {
-                    self.search_query.push(c);
-                    self.filtered_keys();
-                    self.key_list_state.select(None);
+            KeyCode::Char('A') if self.select_view == SelectView::SelectKeyList => {
+                self.new_key_form = Some(NewKeyForm::new());
+            }
+            KeyCode::Char('N' | 'Y' | 'W') if self.select_view == SelectView::SelectKeyList => {
+                if let Some(selected) = self.selected_key() {
+                    let name = escape_bytes(&selected);
+                    let input = String::from_utf8_lossy(&selected).into_owned();
+                    self.prompt = Some(match key {
+                        KeyCode::Char('N') => Prompt::with_input(
+                            PromptKind::RenameKey,
+                            &format!("RENAME {} <new key>", name),
+                            &input,
+                        ),
+                        KeyCode::Char('Y') => Prompt::with_input(
+                            PromptKind::CopyKey,
+                            &format!("COPY {} <destination> [DB n] [REPLACE]", name),
+                            &input,
+                        ),
+                        _ => Prompt::new(PromptKind::MoveKey, &format!("MOVE {} <db>", name)),
+                    });
                 }
-                _ => {}
-            },
-
-            KeyCode::Backspace => match self.select_view {
-                SelectView::SelectKeyList => {
-                    self.search_query.pop();
-                    if !self.search_query.is_empty() {
-                        self.filtered_keys();
+            }
+            KeyCode::Char(' ') if self.select_view == SelectView::SelectKeyList => {
+                self.toggle_mark();
+            }
+            KeyCode::Char('D') if self.select_view == SelectView::SelectKeyList => {
+                let mut keys: Vec<Vec<u8>> = if self.marked_keys.is_empty() {
+                    self.selected_row_keys()
+                } else {
+                    self.marked_keys.iter().cloned().collect()
+                };
+                keys.sort();
+                self.confirm_delete(keys);
+            }
+            KeyCode::Char('K') if self.select_view == SelectView::SelectKeyList => {
+                // 只在有搜索条件时删除全部匹配的键, 避免误删整个库
+                let searching = match self.search_mode {
+                    SearchMode::Local => !self.search_query.is_empty(),
+                    SearchMode::Server => {
+                        self.search_scanner.is_some()
+                            && (!self.search_query.is_empty() || self.search_type().is_some())
                     }
-                    self.key_list_state.select(None);
+                };
+                if searching {
+                    self.task = Some(Task::CollectMatches);
+                } else {
+                    self.status = "Search keys first to delete all matches".to_string();
                 }
-                _ => {}
-            },
+            }
+            KeyCode::Char('/') if self.select_view == SelectView::SelectKeyList => {
+                self.search_focused = true;
+            }
+            KeyCode::Char(c) if self.select_view == SelectView::SelectKeyList => {
+                return self.push_search(c);
+            }
+
+            KeyCode::Backspace if self.select_view == SelectView::SelectKeyList => {
+                return self.pop_search();
+            }
             _ => {}
         }
         Ok(false)
     }
+
+    fn push_search(&mut self, c: char) -> Result<bool> {
+        self.search_query.push(c);
+        self.filtered_keys();
+        self.key_list_state.select(None);
+        Ok(false)
+    }
+
+    fn pop_search(&mut self) -> Result<bool> {
+        self.search_query.pop();
+        if self.search_query.is_empty() {
+            self.search_scanner = None;
+        }
+        self.filtered_keys();
+        self.key_list_state.select(None);
+        Ok(false)
+    }
     /// Get filtered keys list
     fn filtered_keys(&mut self) {
-        if self.search_query.is_empty() {
+        // 服务端搜索需按Enter提交
+        if !self.search_query.is_empty() && self.search_mode == SearchMode::Local {
+            self.search_match_keys.clear();
+            self.filter_new_keys(0);
+        }
+        self.rebuild_key_tree();
+    }
+
+    // 本地搜索时把keys[start..]中匹配的键追加到搜索结果
+    fn filter_new_keys(&mut self, start: usize) {
+        if self.search_query.is_empty() || self.search_mode != SearchMode::Local {
             return;
         }
-        self.search_match_keys = self
-            .keys
+        let query = self.search_query.to_lowercase();
+        let matches = self.keys[start..]
             .iter()
-            .filter(|key| {
-                key.to_lowercase()
-                    .contains(&self.search_query.to_lowercase())
-            })
-            .cloned()
-            .collect();
+            .filter(|key| escape_bytes(key).to_lowercase().contains(&query))
+            .cloned();
+        self.search_match_keys.extend(matches);
     }
 
     // 渲染界面
@@ -279,8 +2043,26 @@ impl App {
             .split(area);
 
         // 顶部状态栏
-        let status_bar = Paragraph::new(self.status.clone())
-            .style(Style::default().bg(Color::Blue).fg(Color::White))
+        let status = match &self.redis_connection {
+            Some(_) if !self.scan_finished() => format!(
+                "db{} | {} | loading {} keys…",
+                self.db,
+                self.status,
+                self.get_keys().len()
+            ),
+            Some(_) => format!("db{} | {}", self.db, self.status),
+            None => self.status.clone(),
+        };
+        let status = match &self.profile_name {
+            Some(name) => format!("{} | {}", name, status),
+            None => status,
+        };
+        let status_bar = Paragraph::new(status)
+            .style(
+                Style::default()
+                    .bg(self.profile_color.unwrap_or(Color::Blue))
+                    .fg(Color::White),
+            )
             .block(Block::default().borders(Borders::NONE));
         frame.render_widget(status_bar, chunks[1]);
         let main_chunks = Layout::default()
@@ -291,6 +2073,36 @@ impl App {
 
         self.render_key_details(frame, main_chunks[1]);
 
+        if let Some(prompt) = &self.prompt {
+            prompt.render(frame, main_chunks[1]);
+        }
+        if let Some(form) = &self.new_key_form {
+            form.render(frame, chunks[0]);
+        }
+        if let Some(confirm) = &self.confirm {
+            confirm.render(frame, chunks[0]);
+        }
+        if let Some(picker) = &mut self.database_picker {
+            picker.render(frame, chunks[0]);
+        }
+        if let Some(picker) = &mut self.profile_picker {
+            picker.render(frame, chunks[0]);
+        }
+        if let Some(Task::Delete(job)) = &self.task {
+            let popup = centered_rect(60, 3, chunks[0]);
+            frame.render_widget(Clear, popup);
+            let gauge = Gauge::default()
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title("Deleting keys (Esc: Stop)"),
+                )
+                .gauge_style(Style::default().fg(Color::Red))
+                .ratio(job.done as f64 / job.total().max(1) as f64)
+                .label(format!("{} {}/{}", job.command(), job.done, job.total()));
+            frame.render_widget(gauge, popup);
+        }
+
         // 底部帮助栏
         let help_text = Line::from(vec![
             Span::raw("KeyMap: "),
@@ -301,7 +2113,15 @@ impl App {
             Span::styled("R", Style::default().add_modifier(Modifier::BOLD)),
             Span::raw("(Refresh) "),
             Span::styled("Enter", Style::default().add_modifier(Modifier::BOLD)),
-            Span::raw("(View Details) "),
+            Span::raw("(View Details/Search) "),
+            Span::styled("M", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("(Search Mode) "),
+            Span::styled("T", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("(Search Type) "),
+            Span::styled("V", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("(Tree View) "),
+            Span::styled("B", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("(Database) "),
             Span::styled("ESC", Style::default().add_modifier(Modifier::BOLD)),
             Span::raw("(Back) "),
         ]);
@@ -323,22 +2143,129 @@ impl App {
             .split(area);
 
         // 渲染搜索框
+        let (search_line, search_title) = match self.search_mode {
+            SearchMode::Local => (
+                format!("Search: {}", self.search_query),
+                "Search Key (local)",
+            ),
+            SearchMode::Server => (
+                format!(
+                    "MATCH: {}  TYPE: {}",
+                    self.search_query,
+                    self.search_type().unwrap_or("*")
+                ),
+                "Search Key (server, Enter to scan)",
+            ),
+        };
+        let (search_line, border_style) = if self.search_focused {
+            (
+                format!("{}█", search_line),
+                Style::default().fg(Color::Green),
+            )
+        } else {
+            (search_line, Style::default())
+        };
         let search_box = Paragraph::new(vec![
-            Line::from(format!("Search: {}", self.search_query)), // 光标占位符
+            Line::from(search_line), // 光标占位符
         ])
         .style(Style::default().fg(Color::Yellow))
-        .block(Block::default().borders(Borders::ALL).title("Search Key"));
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(border_style)
+                .title(search_title),
+        );
         frame.render_widget(search_box, chunks[0]);
 
-        let items: Vec<ListItem> = self
-            .get_keys()
-            .iter()
-            .map(|key| ListItem::new(Line::from((*key).clone())))
-            .collect();
+        // 只为可见的行生成ListItem, 避免大键空间下每次重绘都遍历全部键
+        let height = chunks[1].height.saturating_sub(2).max(1) as usize;
+        let count = self.key_row_count();
+        let selected = self
+            .key_list_state
+            .selected()
+            .map(|index| index.min(count.saturating_sub(1)));
+        let mut offset = self.key_list_state.offset().min(count.saturating_sub(1));
+        if let Some(selected) = selected {
+            if selected < offset {
+                offset = selected;
+            } else if selected >= offset + height {
+                offset = selected + 1 - height;
+            }
+        }
+        let visible = offset..(offset + height).min(count);
+        let items: Vec<ListItem> = if self.tree_view {
+            self.key_tree.rows()[visible]
+                .iter()
+                .map(|row| match row {
+                    TreeRow::Folder {
+                        name,
+                        depth,
+                        count,
+                        expanded,
+                        ..
+                    } => ListItem::new(Line::from(vec![
+                        Span::raw("  ".repeat(*depth)),
+                        Span::styled(
+                            format!(
+                                "{} {}",
+                                if *expanded { "▾" } else { "▸" },
+                                escape_bytes(name)
+                            ),
+                            Style::default().fg(Color::Yellow),
+                        ),
+                        Span::styled(
+                            format!(" ({})", count),
+                            Style::default().fg(Color::DarkGray),
+                        ),
+                    ])),
+                    TreeRow::Key { key, name, depth } => {
+                        let marked = self.marked_keys.contains(key);
+                        ListItem::new(Line::from(format!(
+                            "{}{}{}",
+                            "  ".repeat(*depth),
+                            if marked { "* " } else { "  " },
+                            escape_bytes(name)
+                        )))
+                        .style(marked_style(marked))
+                    }
+                })
+                .collect()
+        } else {
+            self.get_keys()[visible]
+                .iter()
+                .map(|key| {
+                    let marked = self.marked_keys.contains(key);
+                    let text = if marked {
+                        format!("* {}", escape_bytes(key))
+                    } else {
+                        escape_bytes(key)
+                    };
+                    ListItem::new(Line::from(text)).style(marked_style(marked))
+                })
+                .collect()
+        };
 
-        let key_list = List::new(items.clone())
+        let title = if self.tree_view {
+            format!(
+                "Redis Keys ({}/{}) [V: List, ←/→: Fold]",
+                self.get_keys().len(),
+                self.keys.len()
+            )
+        } else {
+            format!(
+                "Redis Keys ({}/{}) [V: Tree]",
+                self.get_keys().len(),
+                self.keys.len()
+            )
+        };
+        let title = if self.marked_keys.is_empty() {
+            title
+        } else {
+            format!("{} {} marked", title, self.marked_keys.len())
+        };
+        let key_list = List::new(items)
             .block(Block::default().borders(Borders::ALL).title(Span::styled(
-                format!("Redis Keys ({}/{})", items.len().clone(), self.keys.len()),
+                title,
                 Style::default().add_modifier(Modifier::BOLD),
             )))
             .style(self.get_selected_style(SelectView::SelectKeyList))
@@ -346,49 +2273,77 @@ impl App {
             .highlight_symbol(">")
             .highlight_spacing(HighlightSpacing::Always)
             .scroll_padding(0);
-        frame.render_stateful_widget(key_list, chunks[1], &mut self.key_list_state);
+        let mut visible_state =
+            ListState::default().with_selected(selected.map(|index| index - offset));
+        frame.render_stateful_widget(key_list, chunks[1], &mut visible_state);
+        *self.key_list_state.offset_mut() = offset;
     }
 
     fn get_selected_style(&self, cur_render_type: SelectView) -> Style {
         if self.select_view == cur_render_type {
             return Style::default().fg(Color::Green);
         }
-        return Style::default();
+        Style::default()
     }
 
     // 渲染键详情
     fn render_key_details(&mut self, frame: &mut Frame, area: Rect) {
-        if let Some(key) = self
-            .get_keys()
-            .get(self.key_list_state.selected().unwrap_or(0))
-        {
+        if let Some(key) = self.selected_key().as_ref() {
+            // 压缩的值多显示一行压缩信息
+            let header_height = match self.key_details.get(key) {
+                Some(details) if details.compression.is_some() => 5,
+                _ => 4,
+            };
             let chunks = Layout::default()
                 .direction(Direction::Vertical)
                 .constraints([
-                    Constraint::Length(4),
+                    Constraint::Length(header_height),
                     Constraint::Min(1),
                     Constraint::Length(3),
                 ])
                 .split(area);
+            self.value_area = chunks[1];
             if let Some(details) = self.key_details.get(key) {
                 // 键基本信息
-                let details_text = vec![
+                let mut details_text = vec![
                     Line::from(vec![
                         Span::styled("Key: ", Style::default().add_modifier(Modifier::BOLD)),
-                        Span::raw(key),
+                        Span::raw(escape_bytes(key)),
                     ]),
                     Line::from(vec![
                         Span::styled("Type: ", Style::default().add_modifier(Modifier::BOLD)),
                         Span::raw(&details.key_type),
                     ]),
                 ];
+                if let Some(compression) = &details.compression {
+                    details_text.push(Line::from(vec![
+                        Span::styled(
+                            "Compression: ",
+                            Style::default().add_modifier(Modifier::BOLD),
+                        ),
+                        Span::raw(format!(
+                            "{} ({} -> {} bytes)",
+                            compression.codec,
+                            compression.compressed_size,
+                            details.value.len()
+                        )),
+                    ]));
+                }
 
-                let details_block = Paragraph::new(details_text).block(
-                    Block::default().borders(Borders::ALL).title(Span::styled(
-                        "Key Details",
-                        Style::default().add_modifier(Modifier::BOLD),
-                    )),
-                );
+                // 自动刷新发现内容变化时在标题中提示
+                let title = match details.changed_at {
+                    Some(changed_at) => Span::styled(
+                        format!("Key Details (changed at {})", changed_at.format("%H:%M:%S")),
+                        Style::default()
+                            .fg(Color::Yellow)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                    None => {
+                        Span::styled("Key Details", Style::default().add_modifier(Modifier::BOLD))
+                    }
+                };
+                let details_block = Paragraph::new(details_text)
+                    .block(Block::default().borders(Borders::ALL).title(title));
                 frame.render_widget(details_block, chunks[0]);
 
                 // 键值内容
@@ -409,8 +2364,8 @@ impl App {
                             let mut rows = vec![];
                             for (field, value) in fields {
                                 rows.push(Row::new(vec![
-                                    Cell::from(Span::raw(field)),
-                                    Cell::from(Span::raw(value)),
+                                    Cell::from(Span::raw(escape_bytes(field))),
+                                    Cell::from(Span::raw(escape_bytes(value))),
                                 ]));
                             }
 
@@ -420,15 +2375,292 @@ impl App {
 
                             let table = Table::new(rows, widths)
                                 .header(header)
-                                .block(Block::default().borders(Borders::ALL).title("Hash Field"))
+                                .block(
+                                    Block::default()
+                                        .borders(Borders::ALL)
+                                        .title("Hash Field [A: Add, U: Update, E: Editor, N: Rename, D: Delete, X: Hex, P: Pretty/Raw, J: Jump]"),
+                                )
                                 .style(self.get_selected_style(SelectView::SelectKeyDetails))
-                                .widths(&[Constraint::Percentage(30), Constraint::Percentage(70)])
+                                .widths([Constraint::Percentage(30), Constraint::Percentage(70)])
                                 .row_highlight_style(Style::new().blue().italic())
                                 .cell_highlight_style(Style::new().red().italic())
                                 .column_highlight_style(Style::new().blue().italic())
                                 .highlight_symbol("->")
                                 .highlight_spacing(HighlightSpacing::Always);
 
+                            // 十六进制模式或字段值可解码时在表格下方显示选中字段的值
+                            let selected_value = self
+                                .key_details_vertical_scroll_state
+                                .selected()
+                                .and_then(|index| fields.values().nth(index));
+                            let decoded = selected_value
+                                .filter(|_| self.value_mode == ValueMode::Pretty)
+                                .and_then(|value| self.decoders.decode(key, value));
+                            let show_value = self.value_mode == ValueMode::Hex || decoded.is_some();
+                            match selected_value {
+                                Some(value) if show_value => {
+                                    let hash_chunks = Layout::default()
+                                        .direction(Direction::Vertical)
+                                        .constraints([
+                                            Constraint::Percentage(50),
+                                            Constraint::Percentage(50),
+                                        ])
+                                        .split(chunks[1]);
+                                    frame.render_stateful_widget(
+                                        table,
+                                        hash_chunks[0],
+                                        &mut self.key_details_vertical_scroll_state,
+                                    );
+                                    self.value_area = hash_chunks[1];
+                                    self.render_value(
+                                        frame,
+                                        hash_chunks[1],
+                                        "Field Value",
+                                        value,
+                                        decoded.as_ref(),
+                                    );
+                                }
+                                _ => frame.render_stateful_widget(
+                                    table,
+                                    chunks[1],
+                                    &mut self.key_details_vertical_scroll_state,
+                                ),
+                            }
+                        }
+                    }
+                    "list" => {
+                        if let Some(list) = &details.list_items {
+                            let header = Row::new(vec![
+                                Cell::from(Span::styled(
+                                    "Index",
+                                    Style::default().add_modifier(Modifier::BOLD),
+                                )),
+                                Cell::from(Span::styled(
+                                    "Value",
+                                    Style::default().add_modifier(Modifier::BOLD),
+                                )),
+                            ]);
+
+                            let rows = list.items.iter().enumerate().map(|(index, value)| {
+                                Row::new(vec![
+                                    Cell::from(Span::raw(index.to_string())),
+                                    Cell::from(Span::raw(escape_bytes(value))),
+                                ])
+                            });
+
+                            let table = Table::new(
+                                rows,
+                                [Constraint::Percentage(15), Constraint::Percentage(85)],
+                            )
+                            .header(header)
+                            .block(Block::default().borders(Borders::ALL).title(format!(
+                                "List Items ({}/{})",
+                                list.items.len(),
+                                list.len
+                            )))
+                            .style(self.get_selected_style(SelectView::SelectKeyDetails))
+                            .row_highlight_style(Style::new().blue().italic())
+                            .highlight_symbol("->")
+                            .highlight_spacing(HighlightSpacing::Always);
+
+                            frame.render_stateful_widget(
+                                table,
+                                chunks[1],
+                                &mut self.key_details_vertical_scroll_state,
+                            );
+                        }
+                    }
+                    "stream" => {
+                        if let Some(stream) = &details.stream {
+                            let stream_chunks = Layout::default()
+                                .direction(Direction::Vertical)
+                                .constraints([Constraint::Length(1), Constraint::Min(1)])
+                                .split(chunks[1]);
+
+                            let tabs = Tabs::new(StreamTab::ALL.iter().map(|tab| tab.title()))
+                                .select(stream.tab.index())
+                                .highlight_style(Style::new().yellow().bold());
+                            frame.render_widget(tabs, stream_chunks[0]);
+
+                            let group = stream.selected_group().unwrap_or("-");
+                            let (table, title) = match stream.tab {
+                                StreamTab::Entries => {
+                                    let header = Row::new(vec![
+                                        Cell::from(Span::styled(
+                                            "ID",
+                                            Style::default().add_modifier(Modifier::BOLD),
+                                        )),
+                                        Cell::from(Span::styled(
+                                            "Fields",
+                                            Style::default().add_modifier(Modifier::BOLD),
+                                        )),
+                                    ]);
+                                    let rows = stream.entries.iter().map(|entry| {
+                                        let lines: Vec<Line> = entry
+                                            .fields
+                                            .iter()
+                                            .map(|(field, value)| {
+                                                Line::from(vec![
+                                                    Span::styled(
+                                                        format!("{}: ", escape_bytes(field)),
+                                                        Style::default()
+                                                            .add_modifier(Modifier::BOLD),
+                                                    ),
+                                                    Span::raw(escape_bytes(value)),
+                                                ])
+                                            })
+                                            .collect();
+                                        Row::new(vec![
+                                            Cell::from(Span::raw(&entry.id)),
+                                            Cell::from(Text::from(lines)),
+                                        ])
+                                        .height(entry.fields.len().max(1) as u16)
+                                    });
+                                    let table = Table::new(
+                                        rows,
+                                        [Constraint::Percentage(30), Constraint::Percentage(70)],
+                                    )
+                                    .header(header);
+                                    let title = format!(
+                                        "Stream Entries ({}/{}) {} [←/→: Tab, O: Order]",
+                                        stream.entries.len(),
+                                        stream.len,
+                                        if stream.descending { "DESC" } else { "ASC" }
+                                    );
+                                    (table, title)
+                                }
+                                StreamTab::Groups => (
+                                    info_table(&stream.groups),
+                                    format!(
+                                        "XINFO GROUPS ({}) [←/→: Tab, G: Next Group]",
+                                        stream.groups.len()
+                                    ),
+                                ),
+                                StreamTab::Consumers => (
+                                    info_table(&stream.consumers),
+                                    format!(
+                                        "XINFO CONSUMERS {} ({}) [←/→: Tab, G: Next Group]",
+                                        group,
+                                        stream.consumers.len()
+                                    ),
+                                ),
+                                StreamTab::Pending => (
+                                    info_table(&stream.pending),
+                                    format!(
+                                        "XPENDING {} ({}) [←/→: Tab, G: Next Group]",
+                                        group,
+                                        stream.pending.len()
+                                    ),
+                                ),
+                            };
+
+                            let table = table
+                                .block(Block::default().borders(Borders::ALL).title(title))
+                                .style(self.get_selected_style(SelectView::SelectKeyDetails))
+                                .row_highlight_style(Style::new().blue().italic())
+                                .highlight_symbol("->")
+                                .highlight_spacing(HighlightSpacing::Always);
+
+                            frame.render_stateful_widget(
+                                table,
+                                stream_chunks[1],
+                                &mut self.key_details_vertical_scroll_state,
+                            );
+                        }
+                    }
+                    "zset" => {
+                        if let Some(zset) = &details.zset_members {
+                            let header = Row::new(vec![
+                                Cell::from(Span::styled(
+                                    "Rank",
+                                    Style::default().add_modifier(Modifier::BOLD),
+                                )),
+                                Cell::from(Span::styled(
+                                    "Member",
+                                    Style::default().add_modifier(Modifier::BOLD),
+                                )),
+                                Cell::from(Span::styled(
+                                    "Score",
+                                    Style::default().add_modifier(Modifier::BOLD),
+                                )),
+                            ]);
+
+                            let rows = zset.members.iter().map(|item| {
+                                Row::new(vec![
+                                    Cell::from(Span::raw(item.rank.to_string())),
+                                    Cell::from(Span::raw(escape_bytes(&item.member))),
+                                    Cell::from(Span::raw(item.score.to_string())),
+                                ])
+                            });
+
+                            let range = match &zset.range {
+                                ZSetRange::Rank => String::new(),
+                                ZSetRange::Score { min, max } => format!(" SCORE {} {}", min, max),
+                                ZSetRange::Lex { min, max } => format!(" LEX {} {}", min, max),
+                            };
+                            let title = format!(
+                                "ZSet Members ({}/{}) {}{} [O: Order, S: Score, L: Lex]",
+                                zset.members.len(),
+                                zset.len,
+                                if zset.descending { "DESC" } else { "ASC" },
+                                range
+                            );
+
+                            let table = Table::new(
+                                rows,
+                                [
+                                    Constraint::Percentage(15),
+                                    Constraint::Percentage(60),
+                                    Constraint::Percentage(25),
+                                ],
+                            )
+                            .header(header)
+                            .block(Block::default().borders(Borders::ALL).title(title))
+                            .style(self.get_selected_style(SelectView::SelectKeyDetails))
+                            .row_highlight_style(Style::new().blue().italic())
+                            .highlight_symbol("->")
+                            .highlight_spacing(HighlightSpacing::Always);
+
+                            frame.render_stateful_widget(
+                                table,
+                                chunks[1],
+                                &mut self.key_details_vertical_scroll_state,
+                            );
+                        }
+                    }
+                    "set" => {
+                        if let Some(set) = &details.set_members {
+                            let header = Row::new(vec![Cell::from(Span::styled(
+                                "Member",
+                                Style::default().add_modifier(Modifier::BOLD),
+                            ))]);
+
+                            let rows = set.members.iter().map(|member| {
+                                Row::new(vec![Cell::from(Span::raw(escape_bytes(member)))])
+                            });
+
+                            let title = match &set.pattern {
+                                Some(pattern) => format!(
+                                    "Set Members ({}/{}) MATCH {} [F: Filter, I: Is Member]",
+                                    set.members.len(),
+                                    set.len,
+                                    pattern
+                                ),
+                                None => format!(
+                                    "Set Members ({}/{}) [F: Filter, I: Is Member]",
+                                    set.members.len(),
+                                    set.len
+                                ),
+                            };
+
+                            let table = Table::new(rows, [Constraint::Percentage(100)])
+                                .header(header)
+                                .block(Block::default().borders(Borders::ALL).title(title))
+                                .style(self.get_selected_style(SelectView::SelectKeyDetails))
+                                .row_highlight_style(Style::new().blue().italic())
+                                .highlight_symbol("->")
+                                .highlight_spacing(HighlightSpacing::Always);
+
                             frame.render_stateful_widget(
                                 table,
                                 chunks[1],
@@ -436,8 +2668,29 @@ impl App {
                             );
                         }
                     }
+                    "string" => match &details.decoded {
+                        Some(decoded) if self.value_mode == ValueMode::Pretty => {
+                            let rows = json::render_lines(&decoded.value, &self.json_collapsed)
+                                .into_iter()
+                                .map(|line| Row::new(vec![Cell::from(line.line)]));
+                            let table = Table::new(rows, [Constraint::Percentage(100)])
+                                .block(Block::default().borders(Borders::ALL).title(format!(
+                                    "Value ({}) [Enter: Fold, E: Edit, P: Raw, X: Hex]",
+                                    decoded.decoder
+                                )))
+                                .style(self.get_selected_style(SelectView::SelectKeyDetails))
+                                .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+                                .highlight_spacing(HighlightSpacing::Always);
+                            frame.render_stateful_widget(
+                                table,
+                                chunks[1],
+                                &mut self.key_details_vertical_scroll_state,
+                            );
+                        }
+                        _ => self.render_value(frame, chunks[1], "Value", &details.value, None),
+                    },
                     _ => {
-                        let value_block = Paragraph::new(details.value.clone())
+                        let value_block = Paragraph::new(escape_multiline(&details.value))
                             .block(Block::default().borders(Borders::ALL).title("Value"))
                             .style(self.get_selected_style(SelectView::SelectKeyDetails))
                             .wrap(Wrap { trim: true });
@@ -445,19 +2698,131 @@ impl App {
                     }
                 }
 
-                let details_block =
-                    Paragraph::new(vec![Line::from(vec![Span::raw(if details.ttl == -1 {
-                        "Never expires".to_string()
-                    } else if details.ttl == -2 {
-                        "Key does not exist".to_string()
-                    } else {
-                        format!("{} seconds", details.ttl)
-                    })])])
-                    .block(Block::default().borders(Borders::ALL).title(
-                        Span::styled("TTL", Style::default().add_modifier(Modifier::BOLD)),
-                    ));
+                // 按过期时间实时计算剩余时间
+                let ttl_text = match (details.pttl, details.expires_at) {
+                    (-1, _) => "Never expires".to_string(),
+                    (-2, _) => "Key does not exist".to_string(),
+                    (_, Some(expires_at)) => {
+                        let at = expires_at.format("%Y-%m-%d %H:%M:%S%.3f %:z");
+                        match (expires_at - Local::now()).num_milliseconds() {
+                            remaining if remaining > 0 => format!(
+                                "{} ({} ms), expires at {}",
+                                ttl::format_duration(remaining),
+                                remaining,
+                                at
+                            ),
+                            _ => format!("Expired at {}", at),
+                        }
+                    }
+                    (pttl, None) => format!("{} ms", pttl),
+                };
+                let details_block = Paragraph::new(vec![Line::from(vec![Span::raw(ttl_text)])])
+                    .block(Block::default().borders(Borders::ALL).title(Span::styled(
+                        "TTL [T: Expire/Persist]",
+                        Style::default().add_modifier(Modifier::BOLD),
+                    )));
                 frame.render_widget(details_block, chunks[2]);
             }
         }
     }
+
+    // 渲染值面板, 按当前模式显示解码结果、文本或十六进制转储
+    fn render_value(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        title: &str,
+        value: &[u8],
+        decoded: Option<&Decoded>,
+    ) {
+        let block = Block::default().borders(Borders::ALL);
+        match (self.value_mode, decoded) {
+            (ValueMode::Pretty, Some(decoded)) => {
+                // 顶行是折叠光标, 反色显示
+                let mut lines: Vec<Line> = json::render_lines(&decoded.value, &self.json_collapsed)
+                    .into_iter()
+                    .map(|line| line.line)
+                    .collect();
+                let cursor = self.value_scroll.min(lines.len().saturating_sub(1));
+                if let Some(line) = lines.get_mut(cursor) {
+                    line.style = Style::new().add_modifier(Modifier::REVERSED);
+                }
+                let value_block = Paragraph::new(lines)
+                    .block(block.title(format!(
+                        "{} ({}) [←/→: Move, Enter: Fold, P: Raw, X: Hex]",
+                        title, decoded.decoder
+                    )))
+                    .style(self.get_selected_style(SelectView::SelectKeyDetails))
+                    .scroll((cursor.min(u16::MAX as usize) as u16, 0));
+                frame.render_widget(value_block, area);
+            }
+            (ValueMode::Pretty | ValueMode::Raw, _) => {
+                let value_block = Paragraph::new(escape_multiline(value))
+                    .block(block.title(format!("{} [P: Pretty/Raw, X: Hex]", title)))
+                    .style(self.get_selected_style(SelectView::SelectKeyDetails))
+                    .wrap(Wrap { trim: true })
+                    .scroll((self.value_scroll.min(u16::MAX as usize) as u16, 0));
+                frame.render_widget(value_block, area);
+            }
+            (ValueMode::Hex, _) => {
+                let start = self.value_scroll.min(hexdump::line_count(value) - 1);
+                let height = area.height.saturating_sub(2) as usize;
+                let value_block = Paragraph::new(hexdump::hex_dump_lines(value, start, height))
+                    .block(block.title(format!(
+                        "{} ({} bytes, offset {:#x}) [X: Text, J: Jump]",
+                        title,
+                        value.len(),
+                        start * BYTES_PER_LINE
+                    )))
+                    .style(self.get_selected_style(SelectView::SelectKeyDetails));
+                frame.render_widget(value_block, area);
+            }
+        }
+    }
+}
+
+// XINFO/XPENDING结果表格, 表头取第一行的字段名
+fn info_table(rows: &InfoRows) -> Table<'_> {
+    let names: Vec<&str> = rows
+        .first()
+        .map(|row| row.iter().map(|(name, _)| name.as_str()).collect())
+        .unwrap_or_default();
+    let header = Row::new(names.iter().map(|name| {
+        Cell::from(Span::styled(
+            *name,
+            Style::default().add_modifier(Modifier::BOLD),
+        ))
+    }));
+    let widths = vec![Constraint::Fill(1); names.len().max(1)];
+    Table::new(
+        rows.iter().map(|row| {
+            Row::new(
+                row.iter()
+                    .map(|(_, value)| Cell::from(Span::raw(value.as_str()))),
+            )
+        }),
+        widths,
+    )
+    .header(header)
+}
+
+// 标记的键以不同颜色显示
+fn marked_style(marked: bool) -> Style {
+    if marked {
+        Style::default().fg(Color::Magenta)
+    } else {
+        Style::default()
+    }
+}
+
+fn same_window<T>(old: &Option<T>, new: &Option<T>, same: impl Fn(&T, &T) -> bool) -> bool {
+    match (old, new) {
+        (Some(old), Some(new)) => same(old, new),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+fn starts_with<T: PartialEq>(items: &[T], prefix: &[T]) -> bool {
+    items.len() >= prefix.len() && items[..prefix.len()] == *prefix
 }
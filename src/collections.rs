@@ -0,0 +1,260 @@
+use anyhow::Result;
+use redis::{Commands, Connection};
+use std::collections::HashSet;
+
+// 每次LRANGE加载的元素数
+pub const LIST_PAGE_SIZE: usize = 200;
+// 每次SSCAN的COUNT
+const SET_SCAN_COUNT: usize = 200;
+// 每次ZRANGE加载的成员数
+const ZSET_PAGE_SIZE: usize = 200;
+
+// 以LRANGE窗口按需加载的列表元素
+pub struct ListItems {
+    pub len: usize,
+    pub items: Vec<Vec<u8>>,
+}
+
+impl ListItems {
+    // 获取列表长度并加载第一页
+    pub fn load(conn: &mut Connection, key: &[u8]) -> Result<Self> {
+        let len: usize = conn.llen(key)?;
+        let mut list = Self {
+            len,
+            items: Vec::new(),
+        };
+        list.load_more(conn, key)?;
+        Ok(list)
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.items.len() >= self.len
+    }
+
+    // 加载下一个窗口, 列表在加载期间变短时以实际返回为准
+    pub fn load_more(&mut self, conn: &mut Connection, key: &[u8]) -> Result<()> {
+        if self.is_complete() {
+            return Ok(());
+        }
+        let start = self.items.len() as isize;
+        let stop = start + LIST_PAGE_SIZE as isize - 1;
+        let items: Vec<Vec<u8>> = conn.lrange(key, start, stop)?;
+        if items.len() < LIST_PAGE_SIZE {
+            self.len = self.items.len() + items.len();
+        }
+        self.items.extend(items);
+        Ok(())
+    }
+}
+
+// 以SSCAN增量加载的集合成员, 支持MATCH过滤
+pub struct SetMembers {
+    pub len: usize,
+    pub members: Vec<Vec<u8>>,
+    pub pattern: Option<String>,
+    cursor: u64,
+    finished: bool,
+    // SSCAN可能重复返回同一个成员, 记录已返回过的成员用于去重
+    seen: HashSet<Vec<u8>>,
+}
+
+impl SetMembers {
+    // 获取集合大小并加载第一页
+    pub fn load(conn: &mut Connection, key: &[u8], pattern: Option<String>) -> Result<Self> {
+        let len: usize = conn.scard(key)?;
+        let mut set = Self {
+            len,
+            members: Vec::new(),
+            pattern,
+            cursor: 0,
+            finished: false,
+            seen: HashSet::new(),
+        };
+        set.load_more(conn, key)?;
+        Ok(set)
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.finished
+    }
+
+    // 继续SSCAN直到拿到至少一个成员或遍历结束
+    pub fn load_more(&mut self, conn: &mut Connection, key: &[u8]) -> Result<()> {
+        while !self.finished {
+            let mut cmd = redis::cmd("SSCAN");
+            cmd.arg(key).arg(self.cursor);
+            if let Some(pattern) = &self.pattern {
+                cmd.arg("MATCH").arg(pattern);
+            }
+            let (cursor, mut members): (u64, Vec<Vec<u8>>) =
+                cmd.arg("COUNT").arg(SET_SCAN_COUNT).query(conn)?;
+            self.cursor = cursor;
+            self.finished = cursor == 0;
+            members.retain(|member| self.seen.insert(member.clone()));
+            if !members.is_empty() {
+                self.members.extend(members);
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+// 有序集合的查询范围
+#[derive(Clone)]
+pub enum ZSetRange {
+    Rank,
+    Score { min: String, max: String },
+    Lex { min: String, max: String },
+}
+
+#[derive(PartialEq)]
+pub struct ZSetMember {
+    pub member: Vec<u8>,
+    pub score: f64,
+    pub rank: usize,
+}
+
+// 以ZRANGE ... WITHSCORES分页加载的有序集合成员
+pub struct ZSetMembers {
+    pub len: usize,
+    pub members: Vec<ZSetMember>,
+    pub descending: bool,
+    pub range: ZSetRange,
+    finished: bool,
+}
+
+impl ZSetMembers {
+    // 获取有序集合大小并加载第一页
+    pub fn load(
+        conn: &mut Connection,
+        key: &[u8],
+        range: ZSetRange,
+        descending: bool,
+    ) -> Result<Self> {
+        let len: usize = conn.zcard(key)?;
+        let mut zset = Self {
+            len,
+            members: Vec::new(),
+            descending,
+            range,
+            finished: false,
+        };
+        zset.load_more(conn, key)?;
+        Ok(zset)
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.finished
+    }
+
+    // 加载下一页, 排名按当前排序方向计算 (ZRANK或ZREVRANK)
+    pub fn load_more(&mut self, conn: &mut Connection, key: &[u8]) -> Result<()> {
+        if self.finished {
+            return Ok(());
+        }
+        let offset = self.members.len();
+        let page: Vec<ZSetMember> = match &self.range {
+            ZSetRange::Rank => {
+                let cmd = if self.descending {
+                    "ZREVRANGE"
+                } else {
+                    "ZRANGE"
+                };
+                let items: Vec<(Vec<u8>, f64)> = redis::cmd(cmd)
+                    .arg(key)
+                    .arg(offset)
+                    .arg(offset + ZSET_PAGE_SIZE - 1)
+                    .arg("WITHSCORES")
+                    .query(conn)?;
+                items
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, (member, score))| ZSetMember {
+                        member,
+                        score,
+                        rank: offset + index,
+                    })
+                    .collect()
+            }
+            ZSetRange::Score { min, max } => {
+                let (cmd, from, to) = if self.descending {
+                    ("ZREVRANGEBYSCORE", max, min)
+                } else {
+                    ("ZRANGEBYSCORE", min, max)
+                };
+                let items: Vec<(Vec<u8>, f64)> = redis::cmd(cmd)
+                    .arg(key)
+                    .arg(from)
+                    .arg(to)
+                    .arg("WITHSCORES")
+                    .arg("LIMIT")
+                    .arg(offset)
+                    .arg(ZSET_PAGE_SIZE)
+                    .query(conn)?;
+                let ranks = self.ranks(conn, key, items.iter().map(|(member, _)| member))?;
+                items
+                    .into_iter()
+                    .zip(ranks)
+                    .map(|((member, score), rank)| ZSetMember {
+                        member,
+                        score,
+                        rank,
+                    })
+                    .collect()
+            }
+            ZSetRange::Lex { min, max } => {
+                let (cmd, from, to) = if self.descending {
+                    ("ZREVRANGEBYLEX", max, min)
+                } else {
+                    ("ZRANGEBYLEX", min, max)
+                };
+                let members: Vec<Vec<u8>> = redis::cmd(cmd)
+                    .arg(key)
+                    .arg(from)
+                    .arg(to)
+                    .arg("LIMIT")
+                    .arg(offset)
+                    .arg(ZSET_PAGE_SIZE)
+                    .query(conn)?;
+                let mut pipe = redis::pipe();
+                for member in &members {
+                    pipe.zscore(key, member);
+                }
+                let scores: Vec<f64> = pipe.query(conn)?;
+                let ranks = self.ranks(conn, key, members.iter())?;
+                members
+                    .into_iter()
+                    .zip(scores)
+                    .zip(ranks)
+                    .map(|((member, score), rank)| ZSetMember {
+                        member,
+                        score,
+                        rank,
+                    })
+                    .collect()
+            }
+        };
+        self.finished = page.len() < ZSET_PAGE_SIZE;
+        self.members.extend(page);
+        Ok(())
+    }
+
+    // 范围查询的结果需要单独查询排名
+    fn ranks<'a>(
+        &self,
+        conn: &mut Connection,
+        key: &[u8],
+        members: impl Iterator<Item = &'a Vec<u8>>,
+    ) -> Result<Vec<usize>> {
+        let mut pipe = redis::pipe();
+        for member in members {
+            if self.descending {
+                pipe.zrevrank(key, member);
+            } else {
+                pipe.zrank(key, member);
+            }
+        }
+        Ok(pipe.query(conn)?)
+    }
+}
@@ -0,0 +1,273 @@
+use anyhow::{bail, Result};
+use std::{
+    fmt,
+    io::{Read, Write},
+    str::FromStr,
+};
+
+use crate::config::CompressionRule;
+use crate::glob::glob_match;
+
+// 解压后的最大字节数, 防止压缩炸弹
+const MAX_DECOMPRESSED_SIZE: u64 = 64 * 1024 * 1024;
+
+// 支持的压缩格式, 块格式没有魔数, 只能通过配置指定
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Codec {
+    Gzip,
+    Zlib,
+    Zstd,
+    Lz4Frame,
+    Lz4Block,
+    SnappyFrame,
+    Snappy,
+    None,
+}
+
+impl fmt::Display for Codec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Codec::Gzip => "gzip",
+            Codec::Zlib => "zlib",
+            Codec::Zstd => "zstd",
+            Codec::Lz4Frame => "lz4",
+            Codec::Lz4Block => "lz4-block",
+            Codec::SnappyFrame => "snappy",
+            Codec::Snappy => "snappy-raw",
+            Codec::None => "none",
+        })
+    }
+}
+
+impl FromStr for Codec {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s {
+            "gzip" => Codec::Gzip,
+            "zlib" => Codec::Zlib,
+            "zstd" => Codec::Zstd,
+            "lz4" => Codec::Lz4Frame,
+            "lz4-block" => Codec::Lz4Block,
+            "snappy" => Codec::SnappyFrame,
+            "snappy-raw" => Codec::Snappy,
+            "none" => Codec::None,
+            _ => bail!(
+                "Unknown compression \"{}\" (gzip, zlib, zstd, lz4, lz4-block, snappy, snappy-raw, none)",
+                s
+            ),
+        })
+    }
+}
+
+// 解压信息, 显示在键详情中
+pub struct Compression {
+    pub codec: Codec,
+    pub compressed_size: usize,
+}
+
+// 按键模式或魔数识别压缩格式并解压
+#[derive(Default)]
+pub struct Decompressor {
+    rules: Vec<(String, Codec)>,
+}
+
+impl Decompressor {
+    // 先添加的规则优先
+    pub fn add_rules(&mut self, rules: Vec<CompressionRule>) -> Result<()> {
+        for rule in rules {
+            let codec = rule.codec.parse()?;
+            self.rules.push((rule.pattern, codec));
+        }
+        Ok(())
+    }
+
+    // 无法识别或解压失败时返回None, 调用方显示原始字节
+    pub fn decompress(&self, key: &[u8], bytes: &[u8]) -> Option<(Compression, Vec<u8>)> {
+        let codec = self
+            .rules
+            .iter()
+            .find(|(pattern, _)| glob_match(pattern.as_bytes(), key))
+            .map(|(_, codec)| *codec)
+            .or_else(|| sniff(bytes))?;
+        let data = decompress(codec, bytes).ok()?;
+        Some((
+            Compression {
+                codec,
+                compressed_size: bytes.len(),
+            },
+            data,
+        ))
+    }
+}
+
+// 根据魔数识别带帧头的格式
+fn sniff(bytes: &[u8]) -> Option<Codec> {
+    match bytes {
+        [0x1f, 0x8b, ..] => Some(Codec::Gzip),
+        [0x28, 0xb5, 0x2f, 0xfd, ..] => Some(Codec::Zstd),
+        [0x04, 0x22, 0x4d, 0x18, ..] => Some(Codec::Lz4Frame),
+        [0xff, 0x06, 0x00, 0x00, b's', b'N', b'a', b'P', b'p', b'Y', ..] => {
+            Some(Codec::SnappyFrame)
+        }
+        // zlib头: CM=8, CINFO<=7, 且头两个字节按大端是31的倍数
+        [cmf, flg, ..]
+            if cmf & 0x0f == 8
+                && cmf >> 4 <= 7
+                && (u16::from(*cmf) << 8 | u16::from(*flg)) % 31 == 0 =>
+        {
+            Some(Codec::Zlib)
+        }
+        _ => None,
+    }
+}
+
+fn decompress(codec: Codec, bytes: &[u8]) -> Result<Vec<u8>> {
+    let reader: Box<dyn Read + '_> = match codec {
+        Codec::None => bail!("Not compressed"),
+        Codec::Gzip => Box::new(flate2::read::GzDecoder::new(bytes)),
+        Codec::Zlib => Box::new(flate2::read::ZlibDecoder::new(bytes)),
+        Codec::Zstd => Box::new(zstd::stream::read::Decoder::new(bytes)?),
+        Codec::Lz4Frame => Box::new(lz4_flex::frame::FrameDecoder::new(bytes)),
+        Codec::SnappyFrame => Box::new(snap::read::FrameDecoder::new(bytes)),
+        Codec::Lz4Block => {
+            // 块格式需要前置的4字节小端原始长度
+            if bytes.len() < 4
+                || u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as u64
+                    > MAX_DECOMPRESSED_SIZE
+            {
+                bail!("Invalid lz4 block size");
+            }
+            return Ok(lz4_flex::block::decompress_size_prepended(bytes)?);
+        }
+        Codec::Snappy => {
+            if snap::raw::decompress_len(bytes)? as u64 > MAX_DECOMPRESSED_SIZE {
+                bail!("Snappy value too large");
+            }
+            return Ok(snap::raw::Decoder::new().decompress_vec(bytes)?);
+        }
+    };
+    let mut data = Vec::new();
+    reader
+        .take(MAX_DECOMPRESSED_SIZE + 1)
+        .read_to_end(&mut data)?;
+    if data.len() as u64 > MAX_DECOMPRESSED_SIZE {
+        bail!("Decompressed value too large");
+    }
+    Ok(data)
+}
+
+// 按原格式重新压缩, 用于写回编辑后的值
+pub fn compress(codec: Codec, data: &[u8]) -> Result<Vec<u8>> {
+    Ok(match codec {
+        Codec::None => data.to_vec(),
+        Codec::Gzip => {
+            let mut encoder =
+                flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(data)?;
+            encoder.finish()?
+        }
+        Codec::Zlib => {
+            let mut encoder =
+                flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(data)?;
+            encoder.finish()?
+        }
+        Codec::Zstd => zstd::encode_all(data, 0)?,
+        Codec::Lz4Frame => {
+            let mut encoder = lz4_flex::frame::FrameEncoder::new(Vec::new());
+            encoder.write_all(data)?;
+            encoder.finish()?
+        }
+        Codec::Lz4Block => lz4_flex::block::compress_prepend_size(data),
+        Codec::SnappyFrame => {
+            let mut encoder = snap::write::FrameEncoder::new(Vec::new());
+            encoder.write_all(data)?;
+            encoder.into_inner().map_err(|e| e.into_error())?
+        }
+        Codec::Snappy => snap::raw::Encoder::new().compress_vec(data)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DATA: &[u8] =
+        b"{\"user\":\"alice\",\"tags\":[\"a\",\"b\",\"c\"],\"padding\":\"aaaaaaaaaaaaaaaa\"}";
+
+    const FRAMED: [Codec; 5] = [
+        Codec::Gzip,
+        Codec::Zlib,
+        Codec::Zstd,
+        Codec::Lz4Frame,
+        Codec::SnappyFrame,
+    ];
+
+    #[test]
+    fn sniff_framed_formats() {
+        for codec in FRAMED {
+            let compressed = compress(codec, DATA).unwrap();
+            assert_eq!(sniff(&compressed), Some(codec), "{}", codec);
+        }
+    }
+
+    #[test]
+    fn sniff_plain_values() {
+        assert_eq!(sniff(b""), None);
+        assert_eq!(sniff(b"x"), None);
+        assert_eq!(sniff(DATA), None);
+        assert_eq!(sniff(b"hello world"), None);
+    }
+
+    #[test]
+    fn round_trip_all_codecs() {
+        let all = FRAMED.into_iter().chain([Codec::Lz4Block, Codec::Snappy]);
+        for codec in all {
+            let compressed = compress(codec, DATA).unwrap();
+            assert_eq!(decompress(codec, &compressed).unwrap(), DATA, "{}", codec);
+        }
+        assert!(decompress(Codec::None, DATA).is_err());
+    }
+
+    #[test]
+    fn decompressor_prefers_rules_over_sniffing() {
+        let mut decompressor = Decompressor::default();
+        decompressor
+            .add_rules(vec![CompressionRule {
+                pattern: "blob:*".to_string(),
+                codec: "snappy-raw".to_string(),
+            }])
+            .unwrap();
+        let raw = compress(Codec::Snappy, DATA).unwrap();
+        let (info, data) = decompressor.decompress(b"blob:1", &raw).unwrap();
+        assert_eq!(info.codec, Codec::Snappy);
+        assert_eq!(info.compressed_size, raw.len());
+        assert_eq!(data, DATA);
+        // 未匹配规则的键只按魔数识别
+        assert!(decompressor.decompress(b"other", &raw).is_none());
+        let gzip = compress(Codec::Gzip, DATA).unwrap();
+        let (info, _) = decompressor.decompress(b"other", &gzip).unwrap();
+        assert_eq!(info.codec, Codec::Gzip);
+    }
+
+    #[test]
+    fn corrupt_input_is_not_decompressed() {
+        let mut gzip = compress(Codec::Gzip, DATA).unwrap();
+        gzip.truncate(12);
+        assert!(Decompressor::default().decompress(b"k", &gzip).is_none());
+        assert!(decompress(Codec::Lz4Block, b"\xff\xff\xff\xff").is_err());
+        assert!(decompress(Codec::Lz4Block, b"\x01").is_err());
+    }
+
+    #[test]
+    fn codec_names_round_trip() {
+        for codec in FRAMED
+            .into_iter()
+            .chain([Codec::Lz4Block, Codec::Snappy, Codec::None])
+        {
+            assert_eq!(codec.to_string().parse::<Codec>().unwrap(), codec);
+        }
+        assert!("brotli".parse::<Codec>().is_err());
+    }
+}
@@ -0,0 +1,202 @@
+use anyhow::{anyhow, Context, Result};
+use ratatui::style::Color;
+use serde::Deserialize;
+use std::{collections::BTreeMap, fs, path::Path, path::PathBuf, str::FromStr};
+
+use crate::protobuf::PROTOBUF_PREFIX;
+
+// 配置文件, 默认位于 <XDG配置目录>/rdsview/config.toml
+#[derive(Deserialize, Default, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub decoders: Vec<DecoderRule>,
+    pub protobuf: ProtobufConfig,
+    pub compression: Vec<CompressionRule>,
+    // 树视图中的命名空间分隔符
+    pub delimiter: Option<String>,
+    // 自动刷新选中键的间隔, 如 "10s", "off" 关闭
+    pub refresh: Option<String>,
+    // 命名的连接档案, 如 [profiles.prod]
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+// 连接档案, 字段与命令行的连接参数对应, 未设置时使用命令行参数或默认值
+#[derive(Deserialize, Default, Clone, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct Profile {
+    pub url: Option<String>,
+    pub socket: Option<PathBuf>,
+    pub host: Option<String>,
+    pub port: Option<u16>,
+    pub user: Option<String>,
+    pub db: Option<i64>,
+    pub tls: bool,
+    pub cacert: Option<PathBuf>,
+    pub cert: Option<PathBuf>,
+    pub key: Option<PathBuf>,
+    pub insecure: bool,
+    // 输出密码的命令, 如 "pass show redis/prod"
+    pub password_command: Option<String>,
+    // 状态栏颜色, 如 "red" 或 "#d75f00", 用于区分环境
+    pub color: Option<String>,
+}
+
+impl Profile {
+    // 以self为准, 未设置的字段取base的值
+    // 连接目标(url、socket、host和port)整体覆盖, 避免与base的目标混用
+    pub fn merged(&self, base: &Profile) -> Profile {
+        let target = if self.has_target() { self } else { base };
+        Profile {
+            url: target.url.clone(),
+            socket: target.socket.clone(),
+            host: target.host.clone(),
+            port: self.port.or(target.port),
+            user: self.user.clone().or_else(|| base.user.clone()),
+            db: self.db.or(base.db),
+            tls: self.tls || base.tls,
+            cacert: self.cacert.clone().or_else(|| base.cacert.clone()),
+            cert: self.cert.clone().or_else(|| base.cert.clone()),
+            key: self.key.clone().or_else(|| base.key.clone()),
+            insecure: self.insecure || base.insecure,
+            password_command: self
+                .password_command
+                .clone()
+                .or_else(|| base.password_command.clone()),
+            color: self.color.clone().or_else(|| base.color.clone()),
+        }
+    }
+
+    pub fn has_target(&self) -> bool {
+        self.url.is_some() || self.socket.is_some() || self.host.is_some()
+    }
+
+    // 指定证书或insecure时也使用TLS
+    pub fn uses_tls(&self) -> bool {
+        self.tls
+            || self.insecure
+            || self.cacert.is_some()
+            || self.cert.is_some()
+            || self.key.is_some()
+            || self
+                .url
+                .as_ref()
+                .is_some_and(|url| url.starts_with("rediss://"))
+    }
+
+    pub fn color(&self) -> Result<Option<Color>> {
+        self.color
+            .as_deref()
+            .map(|color| Color::from_str(color).map_err(|_| anyhow!("invalid color \"{}\"", color)))
+            .transpose()
+    }
+}
+
+// protobuf解码配置: 描述符集合及键模式到消息类型的映射
+#[derive(Deserialize, Default, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProtobufConfig {
+    pub descriptor_set: Option<PathBuf>,
+    pub messages: Vec<ProtobufRule>,
+}
+
+// 键模式到protobuf消息类型的映射, 如 pattern = "user:*", message = "acme.User"
+#[derive(Deserialize, Clone, Debug)]
+pub struct ProtobufRule {
+    pub pattern: String,
+    pub message: String,
+}
+
+impl ProtobufRule {
+    pub fn to_decoder_rule(&self) -> DecoderRule {
+        DecoderRule {
+            pattern: self.pattern.clone(),
+            decoder: format!("{}{}", PROTOBUF_PREFIX, self.message),
+        }
+    }
+}
+
+// 命令行格式: <pattern>=<message>
+impl FromStr for ProtobufRule {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (pattern, message) = s
+            .rsplit_once('=')
+            .ok_or_else(|| anyhow!("expected <pattern>=<message>, got \"{}\"", s))?;
+        Ok(Self {
+            pattern: pattern.to_string(),
+            message: message.to_string(),
+        })
+    }
+}
+
+// 键模式到解码器的映射, 如 pattern = "session:*", decoder = "msgpack"
+#[derive(Deserialize, Clone, Debug)]
+pub struct DecoderRule {
+    pub pattern: String,
+    pub decoder: String,
+}
+
+// 命令行格式: <pattern>=<decoder>
+impl FromStr for DecoderRule {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (pattern, decoder) = s
+            .rsplit_once('=')
+            .ok_or_else(|| anyhow!("expected <pattern>=<decoder>, got \"{}\"", s))?;
+        Ok(Self {
+            pattern: pattern.to_string(),
+            decoder: decoder.to_string(),
+        })
+    }
+}
+
+// 键模式到压缩格式的映射, 如 pattern = "cache:*", codec = "lz4-block"
+#[derive(Deserialize, Clone, Debug)]
+pub struct CompressionRule {
+    pub pattern: String,
+    pub codec: String,
+}
+
+// 命令行格式: <pattern>=<codec>
+impl FromStr for CompressionRule {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (pattern, codec) = s
+            .rsplit_once('=')
+            .ok_or_else(|| anyhow!("expected <pattern>=<codec>, got \"{}\"", s))?;
+        Ok(Self {
+            pattern: pattern.to_string(),
+            codec: codec.to_string(),
+        })
+    }
+}
+
+impl Config {
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("rdsview").join("config.toml"))
+    }
+
+    // 指定路径时文件必须存在, 默认路径不存在时使用空配置
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => match Self::default_path() {
+                Some(path) if path.exists() => path,
+                _ => return Ok(Self::default()),
+            },
+        };
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read config file {}", path.display()))?;
+        let config: Self = toml::from_str(&content)
+            .with_context(|| format!("Failed to parse config file {}", path.display()))?;
+        for (name, profile) in &config.profiles {
+            profile
+                .color()
+                .with_context(|| format!("Invalid profile \"{}\" in {}", name, path.display()))?;
+        }
+        Ok(config)
+    }
+}
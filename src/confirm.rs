@@ -0,0 +1,74 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::prompt::centered_rect;
+
+// 需要确认后才执行的写操作
+pub enum ConfirmAction {
+    SetString {
+        key: Vec<u8>,
+        value: Vec<u8>,
+    },
+    SetHashField {
+        key: Vec<u8>,
+        field: Vec<u8>,
+        value: Vec<u8>,
+    },
+    DeleteHashField {
+        key: Vec<u8>,
+        field: Vec<u8>,
+    },
+    DeleteKeys {
+        keys: Vec<Vec<u8>>,
+    },
+    // 目标键已存在时用RENAME覆盖
+    RenameKey {
+        key: Vec<u8>,
+        new_key: Vec<u8>,
+    },
+}
+
+// 确认弹窗, 显示操作说明或差异, 按Y执行
+pub struct Confirm {
+    pub action: ConfirmAction,
+    pub title: String,
+    pub lines: Vec<Line<'static>>,
+    pub scroll: usize,
+}
+
+impl Confirm {
+    pub fn new(action: ConfirmAction, title: &str, lines: Vec<Line<'static>>) -> Self {
+        Self {
+            action,
+            title: title.to_string(),
+            lines,
+            scroll: 0,
+        }
+    }
+
+    pub fn scroll_by(&mut self, delta: isize) {
+        let max = self.lines.len().saturating_sub(1);
+        self.scroll = self.scroll.saturating_add_signed(delta).min(max);
+    }
+
+    // 在area中央渲染, 高度随内容增长
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let height = (self.lines.len() as u16 + 2).min(area.height.saturating_sub(2));
+        let popup = centered_rect(80, height.max(3), area);
+        frame.render_widget(Clear, popup);
+        let body = Paragraph::new(self.lines.clone())
+            .scroll((self.scroll as u16, 0))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::Yellow))
+                    .title(format!("{} (Y: Confirm, N/Esc: Cancel)", self.title)),
+            );
+        frame.render_widget(body, popup);
+    }
+}
@@ -0,0 +1,148 @@
+use anyhow::Result;
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Cell, Clear, Row, Table, TableState},
+    Frame,
+};
+use redis::Connection;
+use std::collections::HashMap;
+
+use crate::prompt::centered_rect;
+
+// 无法读取databases配置时(如云服务禁用了CONFIG)默认的数据库个数
+const DEFAULT_DATABASES: i64 = 16;
+
+// 数据库的键数统计
+struct Database {
+    index: i64,
+    keys: u64,
+    expires: u64,
+}
+
+// 数据库选择弹窗, 数据来自INFO keyspace
+pub struct DatabasePicker {
+    databases: Vec<Database>,
+    current: i64,
+    state: TableState,
+}
+
+impl DatabasePicker {
+    pub fn load(conn: &mut Connection, current: i64) -> Result<Self> {
+        let info: String = redis::cmd("INFO").arg("keyspace").query(conn)?;
+        let keyspace = parse_keyspace(&info);
+        let count = redis::cmd("CONFIG")
+            .arg("GET")
+            .arg("databases")
+            .query::<Vec<String>>(conn)
+            .ok()
+            .and_then(|reply| reply.get(1).and_then(|count| count.parse().ok()))
+            .unwrap_or(DEFAULT_DATABASES);
+        // 统计中出现的库和当前库总是列出
+        let count = keyspace
+            .keys()
+            .chain([&current])
+            .map(|index| index + 1)
+            .fold(count, i64::max);
+        let databases = (0..count)
+            .map(|index| {
+                let (keys, expires) = keyspace.get(&index).copied().unwrap_or_default();
+                Database {
+                    index,
+                    keys,
+                    expires,
+                }
+            })
+            .collect();
+        let mut state = TableState::default();
+        state.select(usize::try_from(current).ok());
+        Ok(Self {
+            databases,
+            current,
+            state,
+        })
+    }
+
+    pub fn select_previous(&mut self) {
+        self.state.select_previous();
+    }
+
+    pub fn select_next(&mut self) {
+        let last = self.databases.len().saturating_sub(1);
+        let next = self
+            .state
+            .selected()
+            .map_or(0, |index| (index + 1).min(last));
+        self.state.select(Some(next));
+    }
+
+    // 选中的数据库编号
+    pub fn selected(&self) -> Option<i64> {
+        self.databases
+            .get(self.state.selected()?)
+            .map(|database| database.index)
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let height = (self.databases.len() as u16 + 3).min(area.height.saturating_sub(2));
+        let popup = centered_rect(40, height, area);
+        frame.render_widget(Clear, popup);
+        let header = Row::new(["DB", "Keys", "Expires"])
+            .style(Style::default().add_modifier(Modifier::BOLD));
+        let rows = self.databases.iter().map(|database| {
+            let style = if database.index == self.current {
+                Style::default().fg(Color::Green)
+            } else if database.keys == 0 {
+                Style::default().fg(Color::DarkGray)
+            } else {
+                Style::default()
+            };
+            Row::new([
+                Cell::from(format!("db{}", database.index)),
+                Cell::from(database.keys.to_string()),
+                Cell::from(database.expires.to_string()),
+            ])
+            .style(style)
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(6),
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+            ],
+        )
+        .header(header)
+        .row_highlight_style(
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol(">")
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Select database (Enter: SELECT, Esc: Cancel)"),
+        );
+        frame.render_stateful_widget(table, popup, &mut self.state);
+    }
+}
+
+// 解析 "db0:keys=1,expires=0,avg_ttl=0" 格式的行
+fn parse_keyspace(info: &str) -> HashMap<i64, (u64, u64)> {
+    info.lines()
+        .filter_map(|line| {
+            let (name, stats) = line.trim().split_once(':')?;
+            let index = name.strip_prefix("db")?.parse().ok()?;
+            let mut counts = (0, 0);
+            for stat in stats.split(',') {
+                match stat.split_once('=') {
+                    Some(("keys", value)) => counts.0 = value.parse().ok()?,
+                    Some(("expires", value)) => counts.1 = value.parse().ok()?,
+                    _ => {}
+                }
+            }
+            Some((index, counts))
+        })
+        .collect()
+}
@@ -0,0 +1,362 @@
+use anyhow::{bail, Result};
+use serde_json::{Map, Number, Value};
+use std::fmt::Write;
+
+use crate::config::DecoderRule;
+use crate::glob::glob_match;
+
+// 值解码器, 解码结果统一转为JSON值后以JSON树显示
+pub trait ValueDecoder {
+    fn name(&self) -> &str;
+    // 无配置时能否仅凭内容识别该格式
+    fn sniff(&self, bytes: &[u8]) -> bool;
+    fn decode(&self, bytes: &[u8]) -> Option<Value>;
+}
+
+// 解码后的值及所用解码器
+pub struct Decoded {
+    pub decoder: String,
+    pub value: Value,
+}
+
+// 配置中表示不解码的解码器名称
+pub const RAW_DECODER: &str = "raw";
+
+// 解码器集合和键模式到解码器的映射
+pub struct DecoderRegistry {
+    decoders: Vec<Box<dyn ValueDecoder>>,
+    rules: Vec<DecoderRule>,
+}
+
+impl DecoderRegistry {
+    // 内置解码器, 自动识别时按此顺序尝试
+    pub fn new() -> Self {
+        Self {
+            decoders: vec![
+                Box::new(JsonDecoder),
+                Box::new(BsonDecoder),
+                Box::new(MsgPackDecoder),
+                Box::new(CborDecoder),
+            ],
+            rules: Vec::new(),
+        }
+    }
+
+    pub fn register(&mut self, decoder: Box<dyn ValueDecoder>) {
+        self.decoders.push(decoder);
+    }
+
+    // 添加键模式映射, 先添加的规则优先
+    pub fn add_rules(&mut self, rules: Vec<DecoderRule>) -> Result<()> {
+        for rule in &rules {
+            if rule.decoder != RAW_DECODER && self.find(&rule.decoder).is_none() {
+                bail!(
+                    "Unknown decoder \"{}\" for pattern \"{}\"",
+                    rule.decoder,
+                    rule.pattern
+                );
+            }
+        }
+        self.rules.extend(rules);
+        Ok(())
+    }
+
+    fn find(&self, name: &str) -> Option<&dyn ValueDecoder> {
+        self.decoders
+            .iter()
+            .find(|decoder| decoder.name() == name)
+            .map(|decoder| decoder.as_ref())
+    }
+
+    // 键匹配到规则时只使用该解码器, 否则按内容自动识别
+    pub fn decode(&self, key: &[u8], bytes: &[u8]) -> Option<Decoded> {
+        let rule = self
+            .rules
+            .iter()
+            .find(|rule| glob_match(rule.pattern.as_bytes(), key));
+        let decoded = match rule {
+            Some(rule) => {
+                let decoder = self.find(&rule.decoder)?;
+                decoder.decode(bytes).map(|value| (decoder, value))
+            }
+            None => self.decoders.iter().find_map(|decoder| {
+                if decoder.sniff(bytes) {
+                    decoder.decode(bytes).map(|value| (decoder.as_ref(), value))
+                } else {
+                    None
+                }
+            }),
+        };
+        decoded.map(|(decoder, value)| Decoded {
+            decoder: decoder.name().to_string(),
+            value,
+        })
+    }
+}
+
+struct JsonDecoder;
+
+impl ValueDecoder for JsonDecoder {
+    fn name(&self) -> &str {
+        "json"
+    }
+
+    // 只把对象和数组视为JSON, 避免把普通数字或字符串当成JSON
+    fn sniff(&self, bytes: &[u8]) -> bool {
+        matches!(
+            bytes.iter().find(|b| !b.is_ascii_whitespace()),
+            Some(b'{' | b'[')
+        )
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Option<Value> {
+        serde_json::from_slice(bytes).ok()
+    }
+}
+
+struct BsonDecoder;
+
+impl ValueDecoder for BsonDecoder {
+    fn name(&self) -> &str {
+        "bson"
+    }
+
+    // 文档以小端长度开头并以0结尾
+    fn sniff(&self, bytes: &[u8]) -> bool {
+        bytes.len() >= 5
+            && i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize == bytes.len()
+            && bytes.last() == Some(&0)
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Option<Value> {
+        let document = bson::Document::from_reader(bytes).ok()?;
+        Some(bson::Bson::Document(document).into_relaxed_extjson())
+    }
+}
+
+struct MsgPackDecoder;
+
+impl ValueDecoder for MsgPackDecoder {
+    fn name(&self) -> &str {
+        "msgpack"
+    }
+
+    // map或array开头, 且解码需恰好用完所有字节
+    fn sniff(&self, bytes: &[u8]) -> bool {
+        matches!(bytes.first(), Some(0x80..=0x9f | 0xdc..=0xdf))
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Option<Value> {
+        let mut reader = bytes;
+        let value = rmpv::decode::read_value(&mut reader).ok()?;
+        if !reader.is_empty() {
+            return None;
+        }
+        Some(msgpack_to_json(value))
+    }
+}
+
+struct CborDecoder;
+
+impl ValueDecoder for CborDecoder {
+    fn name(&self) -> &str {
+        "cbor"
+    }
+
+    // map、array或自描述标签(0xd9d9f7)开头, 且解码需恰好用完所有字节
+    fn sniff(&self, bytes: &[u8]) -> bool {
+        matches!(bytes.first(), Some(0x80..=0xbf)) || bytes.starts_with(&[0xd9, 0xd9, 0xf7])
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Option<Value> {
+        let mut reader = bytes;
+        let value: ciborium::Value = ciborium::from_reader(&mut reader).ok()?;
+        if !reader.is_empty() {
+            return None;
+        }
+        Some(cbor_to_json(value))
+    }
+}
+
+fn float_to_json(f: f64) -> Value {
+    Number::from_f64(f)
+        .map(Value::Number)
+        .unwrap_or_else(|| Value::String(f.to_string()))
+}
+
+// JSON对象的键只能是字符串, 其它类型的键转为其文本形式
+fn key_to_string(key: Value) -> String {
+    match key {
+        Value::String(s) => s,
+        other => other.to_string(),
+    }
+}
+
+// 字节串转为JSON字符串: 合法UTF-8原样保留(显示时再做JSON转义), 只把非法字节写成\xNN
+fn bytes_to_string(bytes: &[u8]) -> String {
+    let mut text = String::with_capacity(bytes.len());
+    for chunk in bytes.utf8_chunks() {
+        text.push_str(chunk.valid());
+        for b in chunk.invalid() {
+            let _ = write!(text, "\\x{:02x}", b);
+        }
+    }
+    text
+}
+
+fn msgpack_to_json(value: rmpv::Value) -> Value {
+    match value {
+        rmpv::Value::Nil => Value::Null,
+        rmpv::Value::Boolean(b) => Value::Bool(b),
+        rmpv::Value::Integer(i) => match (i.as_i64(), i.as_u64()) {
+            (Some(i), _) => Value::from(i),
+            (_, Some(u)) => Value::from(u),
+            _ => Value::String(i.to_string()),
+        },
+        rmpv::Value::F32(f) => float_to_json(f as f64),
+        rmpv::Value::F64(f) => float_to_json(f),
+        rmpv::Value::String(s) => Value::String(bytes_to_string(s.as_bytes())),
+        rmpv::Value::Binary(b) => Value::String(bytes_to_string(&b)),
+        rmpv::Value::Array(items) => Value::Array(items.into_iter().map(msgpack_to_json).collect()),
+        rmpv::Value::Map(entries) => Value::Object(
+            entries
+                .into_iter()
+                .map(|(k, v)| (key_to_string(msgpack_to_json(k)), msgpack_to_json(v)))
+                .collect::<Map<_, _>>(),
+        ),
+        rmpv::Value::Ext(kind, data) => {
+            Value::String(format!("ext({}) {}", kind, bytes_to_string(&data)))
+        }
+    }
+}
+
+fn cbor_to_json(value: ciborium::Value) -> Value {
+    match value {
+        ciborium::Value::Null => Value::Null,
+        ciborium::Value::Bool(b) => Value::Bool(b),
+        ciborium::Value::Integer(i) => {
+            let i = i128::from(i);
+            match (i64::try_from(i), u64::try_from(i)) {
+                (Ok(i), _) => Value::from(i),
+                (_, Ok(u)) => Value::from(u),
+                _ => Value::String(i.to_string()),
+            }
+        }
+        ciborium::Value::Float(f) => float_to_json(f),
+        ciborium::Value::Text(s) => Value::String(s),
+        ciborium::Value::Bytes(b) => Value::String(bytes_to_string(&b)),
+        // 自描述标签不携带信息
+        ciborium::Value::Tag(55799, inner) => cbor_to_json(*inner),
+        ciborium::Value::Tag(tag, inner) => {
+            let mut map = Map::new();
+            map.insert(format!("tag({})", tag), cbor_to_json(*inner));
+            Value::Object(map)
+        }
+        ciborium::Value::Array(items) => {
+            Value::Array(items.into_iter().map(cbor_to_json).collect())
+        }
+        ciborium::Value::Map(entries) => Value::Object(
+            entries
+                .into_iter()
+                .map(|(k, v)| (key_to_string(cbor_to_json(k)), cbor_to_json(v)))
+                .collect::<Map<_, _>>(),
+        ),
+        _ => Value::Null,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn msgpack(value: &rmpv::Value) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        rmpv::encode::write_value(&mut bytes, value).unwrap();
+        bytes
+    }
+
+    fn cbor(value: &ciborium::Value) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        ciborium::into_writer(value, &mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn msgpack_strings_are_not_escaped_twice() {
+        let value = rmpv::Value::Map(vec![
+            (
+                rmpv::Value::from("path"),
+                rmpv::Value::from("C:\\p \"q\"\nnext"),
+            ),
+            (
+                rmpv::Value::from("bin"),
+                rmpv::Value::Binary(b"ok\xff".to_vec()),
+            ),
+        ]);
+        let decoded = MsgPackDecoder.decode(&msgpack(&value)).unwrap();
+        assert_eq!(
+            decoded,
+            json!({"path": "C:\\p \"q\"\nnext", "bin": "ok\\xff"})
+        );
+        assert_eq!(decoded["path"].to_string(), r#""C:\\p \"q\"\nnext""#);
+    }
+
+    #[test]
+    fn msgpack_invalid_utf8_string() {
+        // fixarray(1), fixstr(3) "a\xfeb"
+        let bytes = [0x91, 0xa3, b'a', 0xfe, b'b'];
+        assert_eq!(MsgPackDecoder.decode(&bytes).unwrap(), json!(["a\\xfeb"]));
+    }
+
+    #[test]
+    fn msgpack_rejects_trailing_bytes() {
+        let mut bytes = msgpack(&rmpv::Value::Array(vec![rmpv::Value::from(1)]));
+        bytes.push(0);
+        assert!(MsgPackDecoder.decode(&bytes).is_none());
+    }
+
+    #[test]
+    fn cbor_strings_and_bytes() {
+        let value = ciborium::Value::Map(vec![
+            (
+                ciborium::Value::Text("path".into()),
+                ciborium::Value::Text("C:\\p\n".into()),
+            ),
+            (
+                ciborium::Value::Text("bin".into()),
+                ciborium::Value::Bytes(b"\\\xff".to_vec()),
+            ),
+            (
+                ciborium::Value::Integer(1.into()),
+                ciborium::Value::Tag(1, Box::new(ciborium::Value::Integer(1_700_000_000.into()))),
+            ),
+        ]);
+        assert_eq!(
+            CborDecoder.decode(&cbor(&value)).unwrap(),
+            json!({"path": "C:\\p\n", "bin": "\\\\xff", "1": {"tag(1)": 1_700_000_000}})
+        );
+    }
+
+    #[test]
+    fn registry_sniffs_and_honours_rules() {
+        let mut registry = DecoderRegistry::new();
+        registry
+            .add_rules(vec![DecoderRule {
+                pattern: "raw:*".to_string(),
+                decoder: RAW_DECODER.to_string(),
+            }])
+            .unwrap();
+        let bytes = msgpack(&rmpv::Value::Array(vec![rmpv::Value::from("x")]));
+        assert_eq!(registry.decode(b"k", &bytes).unwrap().decoder, "msgpack");
+        assert_eq!(registry.decode(b"k", b"{\"a\":1}").unwrap().decoder, "json");
+        assert!(registry.decode(b"k", b"plain").is_none());
+        assert!(registry.decode(b"raw:1", b"{\"a\":1}").is_none());
+        assert!(registry
+            .add_rules(vec![DecoderRule {
+                pattern: "*".to_string(),
+                decoder: "yaml".to_string(),
+            }])
+            .is_err());
+    }
+}
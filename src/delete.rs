@@ -0,0 +1,60 @@
+use anyhow::Result;
+use redis::Connection;
+
+// 每批删除的键数
+pub const DELETE_BATCH_SIZE: usize = 500;
+
+// 分批删除一组键, 每批之间刷新界面显示进度
+pub struct BulkDelete {
+    keys: Vec<Vec<u8>>,
+    // 已发送删除命令的键数
+    pub done: usize,
+    // 服务端实际删除的键数, 已不存在的键不计入
+    pub deleted: usize,
+    unlink: bool,
+}
+
+impl BulkDelete {
+    pub fn new(keys: Vec<Vec<u8>>, unlink: bool) -> Self {
+        Self {
+            keys,
+            done: 0,
+            deleted: 0,
+            unlink,
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.done >= self.keys.len()
+    }
+
+    pub fn command(&self) -> &'static str {
+        if self.unlink {
+            "UNLINK"
+        } else {
+            "DEL"
+        }
+    }
+
+    // 已处理的键, 用于从键列表中移除
+    pub fn processed(&self) -> &[Vec<u8>] {
+        &self.keys[..self.done]
+    }
+
+    // 删除下一批键
+    pub fn step(&mut self, conn: &mut Connection) -> Result<()> {
+        let end = (self.done + DELETE_BATCH_SIZE).min(self.keys.len());
+        if self.done < end {
+            let deleted: usize = redis::cmd(self.command())
+                .arg(&self.keys[self.done..end])
+                .query(conn)?;
+            self.deleted += deleted;
+            self.done = end;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,36 @@
+use std::fmt::Write;
+
+// 将任意字节转为可显示文本: 合法UTF-8原样显示, 其余字节和控制字符转义为\xNN
+pub fn escape_bytes(bytes: &[u8]) -> String {
+    let mut text = String::with_capacity(bytes.len());
+    for chunk in bytes.utf8_chunks() {
+        for c in chunk.valid().chars() {
+            match c {
+                '\n' => text.push_str("\\n"),
+                '\r' => text.push_str("\\r"),
+                '\t' => text.push_str("\\t"),
+                '\\' => text.push_str("\\\\"),
+                c if c.is_control() => {
+                    let mut buf = [0; 4];
+                    for b in c.encode_utf8(&mut buf).bytes() {
+                        let _ = write!(text, "\\x{:02x}", b);
+                    }
+                }
+                c => text.push(c),
+            }
+        }
+        for b in chunk.invalid() {
+            let _ = write!(text, "\\x{:02x}", b);
+        }
+    }
+    text
+}
+
+// 同escape_bytes, 但保留换行, 用于多行的值面板
+pub fn escape_multiline(bytes: &[u8]) -> String {
+    bytes
+        .split(|b| *b == b'\n')
+        .map(escape_bytes)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,68 @@
+use anyhow::{bail, Context, Result};
+use ratatui::{
+    style::{Color, Style},
+    text::Line,
+};
+use similar::{ChangeTag, TextDiff};
+use std::{env, fs, io::Write, process::Command};
+
+// 等待在外部编辑器中编辑的值, 由主循环挂起界面后处理
+pub struct EditRequest {
+    pub key: Vec<u8>,
+    // 编辑哈希字段时为字段名, 否则编辑字符串值
+    pub field: Option<Vec<u8>>,
+    pub value: Vec<u8>,
+    // 临时文件后缀, 便于编辑器识别语法
+    pub suffix: &'static str,
+}
+
+// 用$VISUAL或$EDITOR(默认vi)编辑内容, 返回保存后的内容
+pub fn edit(value: &[u8], suffix: &str) -> Result<Vec<u8>> {
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    let mut file = tempfile::Builder::new()
+        .prefix("rdsview-")
+        .suffix(suffix)
+        .tempfile()?;
+    file.write_all(value)?;
+    file.flush()?;
+
+    // 编辑器命令可能带参数, 如 "code --wait"
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().context("$EDITOR is empty")?;
+    let status = Command::new(program)
+        .args(parts)
+        .arg(file.path())
+        .status()
+        .with_context(|| format!("Failed to start editor \"{}\"", editor))?;
+    if !status.success() {
+        bail!("Editor \"{}\" exited with {}", editor, status);
+    }
+    Ok(fs::read(file.path())?)
+}
+
+// 按行对比修改前后的内容, 只保留变化附近的上下文
+pub fn diff_lines(old: &[u8], new: &[u8]) -> Vec<Line<'static>> {
+    let old = String::from_utf8_lossy(old);
+    let new = String::from_utf8_lossy(new);
+    let diff = TextDiff::from_lines(old.as_ref(), new.as_ref());
+    let mut lines = Vec::new();
+    for (index, group) in diff.grouped_ops(3).iter().enumerate() {
+        if index > 0 {
+            lines.push(Line::styled("…", Style::default().fg(Color::DarkGray)));
+        }
+        for op in group {
+            for change in diff.iter_changes(op) {
+                let (sign, style) = match change.tag() {
+                    ChangeTag::Delete => ("-", Style::default().fg(Color::Red)),
+                    ChangeTag::Insert => ("+", Style::default().fg(Color::Green)),
+                    ChangeTag::Equal => (" ", Style::default()),
+                };
+                let text = change.value().trim_end_matches(['\n', '\r']);
+                lines.push(Line::styled(format!("{}{}", sign, text), style));
+            }
+        }
+    }
+    lines
+}
@@ -0,0 +1,263 @@
+use anyhow::{bail, Result};
+use crossterm::event::KeyCode;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::prompt::centered_rect;
+use crate::ttl::{self, Expiry};
+
+// 可新建的键类型
+pub const NEW_KEY_TYPES: [&str; 6] = ["string", "hash", "list", "set", "zset", "stream"];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum FormField {
+    Type,
+    Name,
+    Value,
+    Ttl,
+}
+
+const FIELDS: [FormField; 4] = [
+    FormField::Type,
+    FormField::Name,
+    FormField::Value,
+    FormField::Ttl,
+];
+
+// 新建键的表单
+pub struct NewKeyForm {
+    type_index: usize,
+    pub name: String,
+    value: String,
+    ttl: String,
+    focus: usize,
+}
+
+impl NewKeyForm {
+    pub fn new() -> Self {
+        Self {
+            type_index: 0,
+            name: String::new(),
+            value: String::new(),
+            ttl: String::new(),
+            focus: 1,
+        }
+    }
+
+    pub fn key_type(&self) -> &'static str {
+        NEW_KEY_TYPES[self.type_index]
+    }
+
+    // 各类型初始值的输入格式, 与redis-cli的参数一致
+    fn value_hint(&self) -> &'static str {
+        match self.key_type() {
+            "string" => "value (taken literally)",
+            "hash" => "field value [field value ...]",
+            "list" => "element [element ...]",
+            "set" => "member [member ...]",
+            "zset" => "score member [score member ...]",
+            _ => "field value [field value ...]",
+        }
+    }
+
+    // 处理除Enter和Esc之外的按键: 切换字段、切换类型和编辑文本
+    pub fn input(&mut self, key: KeyCode) {
+        let field = FIELDS[self.focus];
+        match key {
+            KeyCode::Tab | KeyCode::Down => self.focus = (self.focus + 1) % FIELDS.len(),
+            KeyCode::BackTab | KeyCode::Up => {
+                self.focus = (self.focus + FIELDS.len() - 1) % FIELDS.len()
+            }
+            KeyCode::Left if field == FormField::Type => {
+                self.type_index = (self.type_index + NEW_KEY_TYPES.len() - 1) % NEW_KEY_TYPES.len()
+            }
+            KeyCode::Right | KeyCode::Char(' ') if field == FormField::Type => {
+                self.type_index = (self.type_index + 1) % NEW_KEY_TYPES.len()
+            }
+            KeyCode::Char(c) => {
+                if let Some(text) = self.text_mut(field) {
+                    text.push(c);
+                }
+            }
+            KeyCode::Backspace => {
+                if let Some(text) = self.text_mut(field) {
+                    text.pop();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn text_mut(&mut self, field: FormField) -> Option<&mut String> {
+        match field {
+            FormField::Type => None,
+            FormField::Name => Some(&mut self.name),
+            FormField::Value => Some(&mut self.value),
+            FormField::Ttl => Some(&mut self.ttl),
+        }
+    }
+
+    // 过期时长如 90 或 1h30m, 为空表示不过期
+    pub fn expiry(&self) -> Result<Option<Expiry>> {
+        if self.ttl.trim().is_empty() {
+            return Ok(None);
+        }
+        ttl::parse_duration(&self.ttl).map(|ms| Some(Expiry::In(ms)))
+    }
+
+    // 生成创建键的命令
+    pub fn create_command(&self) -> Result<redis::Cmd> {
+        if self.name.is_empty() {
+            bail!("Key name cannot be empty");
+        }
+        if self.key_type() == "string" {
+            let mut cmd = redis::cmd("SET");
+            cmd.arg(&self.name).arg(&self.value).arg("NX");
+            return Ok(cmd);
+        }
+        let args = split_args(&self.value)?;
+        if args.is_empty() {
+            bail!("Enter at least one {}", self.value_hint());
+        }
+        let paired = matches!(self.key_type(), "hash" | "zset" | "stream");
+        if paired && args.len() % 2 != 0 {
+            bail!("Expected {}", self.value_hint());
+        }
+        let mut cmd = match self.key_type() {
+            "hash" => redis::cmd("HSET"),
+            "list" => redis::cmd("RPUSH"),
+            "set" => redis::cmd("SADD"),
+            "zset" => redis::cmd("ZADD"),
+            _ => redis::cmd("XADD"),
+        };
+        cmd.arg(&self.name);
+        if self.key_type() == "stream" {
+            cmd.arg("*");
+        }
+        cmd.arg(args);
+        Ok(cmd)
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let popup = centered_rect(70, 7, area);
+        frame.render_widget(Clear, popup);
+        let label = |field: FormField, name: &'static str| {
+            let style = if FIELDS[self.focus] == field {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().add_modifier(Modifier::BOLD)
+            };
+            Span::styled(format!("{:<7}", name), style)
+        };
+        let cursor = |field: FormField| if FIELDS[self.focus] == field { "_" } else { "" };
+        let lines = vec![
+            Line::from(vec![
+                label(FormField::Type, "Type:"),
+                Span::raw(format!("< {} >", self.key_type())),
+            ]),
+            Line::from(vec![
+                label(FormField::Name, "Key:"),
+                Span::raw(format!("{}{}", self.name, cursor(FormField::Name))),
+            ]),
+            Line::from(vec![
+                label(FormField::Value, "Value:"),
+                Span::raw(format!("{}{}", self.value, cursor(FormField::Value))),
+            ]),
+            Line::from(vec![
+                label(FormField::Ttl, "TTL:"),
+                Span::raw(format!("{}{}", self.ttl, cursor(FormField::Ttl))),
+                Span::styled("  (e.g. 90, 1h30m)", Style::default().fg(Color::DarkGray)),
+            ]),
+            Line::styled(
+                format!("Value format: {}", self.value_hint()),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ];
+        let form = Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("New Key (Tab: Next, ←/→: Type, Enter: Create, Esc: Cancel)"),
+        );
+        frame.render_widget(form, popup);
+    }
+}
+
+// 按空白拆分参数, 支持双引号(可用\转义)和单引号, 规则与redis-cli相同
+pub fn split_args(input: &str) -> Result<Vec<String>> {
+    let mut args = Vec::new();
+    let mut chars = input.chars().peekable();
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        let Some(&first) = chars.peek() else {
+            return Ok(args);
+        };
+        let mut arg = String::new();
+        if first == '"' || first == '\'' {
+            chars.next();
+            loop {
+                match chars.next() {
+                    Some('\\') if first == '"' => match chars.next() {
+                        Some('n') => arg.push('\n'),
+                        Some('r') => arg.push('\r'),
+                        Some('t') => arg.push('\t'),
+                        Some(c) => arg.push(c),
+                        None => bail!("Unterminated quote"),
+                    },
+                    Some(c) if c == first => break,
+                    Some(c) => arg.push(c),
+                    None => bail!("Unterminated quote"),
+                }
+            }
+            if chars.peek().is_some_and(|c| !c.is_whitespace()) {
+                bail!("Closing quote must be followed by a space");
+            }
+        } else {
+            while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                arg.push(c);
+            }
+        }
+        args.push(arg);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_plain_words() {
+        assert_eq!(split_args("a b  c").unwrap(), ["a", "b", "c"]);
+        assert_eq!(split_args("  1.5\tmember \n").unwrap(), ["1.5", "member"]);
+        assert!(split_args("").unwrap().is_empty());
+        assert!(split_args("   ").unwrap().is_empty());
+    }
+
+    #[test]
+    fn split_quoted() {
+        assert_eq!(
+            split_args(r#"field "value with spaces""#).unwrap(),
+            ["field", "value with spaces"]
+        );
+        assert_eq!(
+            split_args(r#"'single \n' "double\n\t\"q\"""#).unwrap(),
+            ["single \\n", "double\n\t\"q\""]
+        );
+        assert_eq!(split_args(r#""" ''"#).unwrap(), ["", ""]);
+        assert_eq!(split_args("it's").unwrap(), ["it's"]);
+    }
+
+    #[test]
+    fn split_rejects_bad_quotes() {
+        assert!(split_args(r#""open"#).is_err());
+        assert!(split_args("'open").is_err());
+        assert!(split_args(r#""trailing\"#).is_err());
+        assert!(split_args(r#""a"b"#).is_err());
+    }
+}
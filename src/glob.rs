@@ -0,0 +1,148 @@
+// Redis风格的glob匹配 (*, ?, [abc], [^a-z], \x), 与SCAN MATCH语义一致
+pub fn glob_match(pattern: &[u8], text: &[u8]) -> bool {
+    let (mut p, mut t) = (0, 0);
+    // 最近一个*的位置, 用于回溯
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() {
+            match pattern[p] {
+                b'*' => {
+                    star = Some((p, t));
+                    p += 1;
+                    continue;
+                }
+                b'?' => {
+                    p += 1;
+                    t += 1;
+                    continue;
+                }
+                b'[' => {
+                    if let Some((matched, next)) = match_class(pattern, p, text[t]) {
+                        if matched {
+                            p = next;
+                            t += 1;
+                            continue;
+                        }
+                    }
+                }
+                b'\\' if p + 1 < pattern.len() => {
+                    if pattern[p + 1] == text[t] {
+                        p += 2;
+                        t += 1;
+                        continue;
+                    }
+                }
+                c => {
+                    if c == text[t] {
+                        p += 1;
+                        t += 1;
+                        continue;
+                    }
+                }
+            }
+        }
+        match star {
+            Some((star_p, star_t)) => {
+                p = star_p + 1;
+                t = star_t + 1;
+                star = Some((star_p, star_t + 1));
+            }
+            None => return false,
+        }
+    }
+    pattern[p..].iter().all(|c| *c == b'*')
+}
+
+// 匹配[...]字符类, 返回是否匹配及类之后的位置
+fn match_class(pattern: &[u8], start: usize, c: u8) -> Option<(bool, usize)> {
+    let mut i = start + 1;
+    let negate = pattern.get(i) == Some(&b'^');
+    if negate {
+        i += 1;
+    }
+    let mut matched = false;
+    while i < pattern.len() && pattern[i] != b']' {
+        if pattern[i] == b'\\' && i + 1 < pattern.len() {
+            matched |= pattern[i + 1] == c;
+            i += 2;
+        } else if i + 2 < pattern.len() && pattern[i + 1] == b'-' && pattern[i + 2] != b']' {
+            let (low, high) = if pattern[i] <= pattern[i + 2] {
+                (pattern[i], pattern[i + 2])
+            } else {
+                (pattern[i + 2], pattern[i])
+            };
+            matched |= low <= c && c <= high;
+            i += 3;
+        } else {
+            matched |= pattern[i] == c;
+            i += 1;
+        }
+    }
+    if i >= pattern.len() {
+        return None;
+    }
+    Some((matched != negate, i + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(pattern: &str, text: &str) -> bool {
+        glob_match(pattern.as_bytes(), text.as_bytes())
+    }
+
+    #[test]
+    fn literal_and_wildcards() {
+        assert!(matches("user:1", "user:1"));
+        assert!(!matches("user:1", "user:10"));
+        assert!(matches("*", ""));
+        assert!(matches("user:*", "user:"));
+        assert!(matches("user:*:name", "user:42:name"));
+        assert!(!matches("user:*:name", "user:42:email"));
+        assert!(matches("*:*:*", "a:b:c"));
+        assert!(matches("h?llo", "hello"));
+        assert!(!matches("h?llo", "hllo"));
+        assert!(matches("a*b*c", "aXbYbZc"));
+        assert!(!matches("a*b*c", "aXbYbZ"));
+    }
+
+    #[test]
+    fn case_sensitive() {
+        assert!(matches("Device:*", "Device:1"));
+        assert!(!matches("Device:*", "device:1"));
+    }
+
+    #[test]
+    fn character_classes() {
+        assert!(matches("h[ae]llo", "hallo"));
+        assert!(!matches("h[ae]llo", "hillo"));
+        assert!(matches("h[^e]llo", "hallo"));
+        assert!(!matches("h[^e]llo", "hello"));
+        assert!(matches("key[0-9]", "key7"));
+        assert!(matches("key[9-0]", "key7"));
+        assert!(!matches("key[0-9]", "keyx"));
+        assert!(matches("a[\\]]b", "a]b"));
+        assert!(matches("a[x-]", "a-"));
+    }
+
+    #[test]
+    fn unterminated_class_is_literal_mismatch() {
+        assert!(!matches("a[bc", "ab"));
+        assert!(!matches("a[bc", "a[bc"));
+    }
+
+    #[test]
+    fn escapes() {
+        assert!(matches("a\\*b", "a*b"));
+        assert!(!matches("a\\*b", "axb"));
+        assert!(matches("what\\?", "what?"));
+        assert!(matches("a\\", "a\\"));
+    }
+
+    #[test]
+    fn binary_keys() {
+        assert!(glob_match(b"\xff*", b"\xff\x00\x01"));
+        assert!(!glob_match(b"\xfe*", b"\xff\x00"));
+    }
+}
@@ -0,0 +1,64 @@
+use ratatui::{
+    style::{Color, Style},
+    text::{Line, Span},
+};
+
+// 每行显示的字节数
+pub const BYTES_PER_LINE: usize = 16;
+
+// 总行数, 用于限制滚动范围
+pub fn line_count(bytes: &[u8]) -> usize {
+    bytes.len().div_ceil(BYTES_PER_LINE).max(1)
+}
+
+// 从第start行开始生成最多height行: 偏移量、十六进制、可打印字符
+pub fn hex_dump_lines(bytes: &[u8], start: usize, height: usize) -> Vec<Line<'static>> {
+    bytes
+        .chunks(BYTES_PER_LINE)
+        .enumerate()
+        .skip(start)
+        .take(height)
+        .map(|(index, chunk)| {
+            let mut hex = String::with_capacity(BYTES_PER_LINE * 3 + 1);
+            for i in 0..BYTES_PER_LINE {
+                if i == BYTES_PER_LINE / 2 {
+                    hex.push(' ');
+                }
+                match chunk.get(i) {
+                    Some(b) => hex.push_str(&format!("{:02x} ", b)),
+                    None => hex.push_str("   "),
+                }
+            }
+            let ascii: String = chunk
+                .iter()
+                .map(|b| {
+                    if b.is_ascii_graphic() || *b == b' ' {
+                        *b as char
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+            Line::from(vec![
+                Span::styled(
+                    format!("{:08x}  ", index * BYTES_PER_LINE),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::raw(hex),
+                Span::styled(format!(" |{}|", ascii), Style::default().fg(Color::Cyan)),
+            ])
+        })
+        .collect()
+}
+
+// 解析偏移量, 支持十进制和0x前缀的十六进制
+pub fn parse_offset(input: &str) -> Option<usize> {
+    let input = input.trim();
+    match input
+        .strip_prefix("0x")
+        .or_else(|| input.strip_prefix("0X"))
+    {
+        Some(hex) => usize::from_str_radix(hex, 16).ok(),
+        None => input.parse().ok(),
+    }
+}
@@ -0,0 +1,129 @@
+use ratatui::{
+    style::{Color, Style},
+    text::{Line, Span},
+};
+use serde_json::Value;
+use std::collections::HashSet;
+
+// JSON树的一行, 对象和数组的起始行带有路径以便折叠
+pub struct JsonLine {
+    pub line: Line<'static>,
+    pub path: Option<String>,
+}
+
+// 生成带语法高亮的格式化行, collapsed中的路径只显示摘要
+pub fn render_lines(value: &Value, collapsed: &HashSet<String>) -> Vec<JsonLine> {
+    let mut lines = Vec::new();
+    walk(value, None, String::new(), 0, false, collapsed, &mut lines);
+    lines
+}
+
+fn walk(
+    value: &Value,
+    key: Option<&str>,
+    path: String,
+    depth: usize,
+    comma: bool,
+    collapsed: &HashSet<String>,
+    lines: &mut Vec<JsonLine>,
+) {
+    let mut prefix = vec![Span::raw("  ".repeat(depth))];
+    if let Some(key) = key {
+        prefix.push(Span::styled(
+            Value::String(key.to_string()).to_string(),
+            Style::default().fg(Color::Blue),
+        ));
+        prefix.push(Span::raw(": "));
+    }
+    let comma = if comma { "," } else { "" };
+
+    let (open, close, len) = match value {
+        Value::Object(map) => ("{", "}", map.len()),
+        Value::Array(items) => ("[", "]", items.len()),
+        scalar => {
+            prefix.push(scalar_span(scalar));
+            prefix.push(Span::raw(comma));
+            lines.push(JsonLine {
+                line: Line::from(prefix),
+                path: None,
+            });
+            return;
+        }
+    };
+
+    if len == 0 {
+        prefix.push(Span::raw(format!("{}{}{}", open, close, comma)));
+        lines.push(JsonLine {
+            line: Line::from(prefix),
+            path: None,
+        });
+        return;
+    }
+
+    if collapsed.contains(&path) {
+        prefix.push(Span::raw(open));
+        prefix.push(Span::styled(
+            format!(" … {} items ", len),
+            Style::default().fg(Color::DarkGray),
+        ));
+        prefix.push(Span::raw(format!("{}{}", close, comma)));
+        lines.push(JsonLine {
+            line: Line::from(prefix),
+            path: Some(path),
+        });
+        return;
+    }
+
+    prefix.push(Span::raw(open));
+    lines.push(JsonLine {
+        line: Line::from(prefix),
+        path: Some(path.clone()),
+    });
+    match value {
+        Value::Object(map) => {
+            for (index, (child_key, child)) in map.iter().enumerate() {
+                walk(
+                    child,
+                    Some(child_key),
+                    format!(
+                        "{}/{}",
+                        path,
+                        child_key.replace('~', "~0").replace('/', "~1")
+                    ),
+                    depth + 1,
+                    index + 1 < len,
+                    collapsed,
+                    lines,
+                );
+            }
+        }
+        Value::Array(items) => {
+            for (index, child) in items.iter().enumerate() {
+                walk(
+                    child,
+                    None,
+                    format!("{}/{}", path, index),
+                    depth + 1,
+                    index + 1 < len,
+                    collapsed,
+                    lines,
+                );
+            }
+        }
+        _ => {}
+    }
+    lines.push(JsonLine {
+        line: Line::from(format!("{}{}{}", "  ".repeat(depth), close, comma)),
+        path: None,
+    });
+}
+
+fn scalar_span(value: &Value) -> Span<'static> {
+    let color = match value {
+        Value::String(_) => Color::Green,
+        Value::Number(_) => Color::Cyan,
+        Value::Bool(_) => Color::Yellow,
+        _ => Color::Magenta,
+    };
+    Span::styled(value.to_string(), Style::default().fg(color))
+}
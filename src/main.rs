@@ -1,6 +1,31 @@
 mod app;
-use anyhow::Result;
+mod collections;
+mod compress;
+mod config;
+mod confirm;
+mod connection;
+mod database;
+mod decode;
+mod delete;
+mod display;
+mod editor;
+mod form;
+mod glob;
+mod hexdump;
+mod json;
+mod profiles;
+mod prompt;
+mod protobuf;
+mod scan;
+mod stream;
+mod tls;
+mod tree;
+mod ttl;
+use anyhow::{anyhow, Result};
 use app::App;
+use compress::Decompressor;
+use config::{CompressionRule, Config, DecoderRule, Profile, ProtobufRule};
+use decode::DecoderRegistry;
 
 use clap::Parser;
 use crossterm::{
@@ -11,7 +36,9 @@ use crossterm::{
 use ratatui::{prelude::CrosstermBackend, Terminal};
 
 use std::io; // Ensure these imports exist
-             // 初始化终端
+use std::path::PathBuf;
+use std::time::Duration;
+// 初始化终端
 fn init_terminal() -> Result<Terminal<CrosstermBackend<io::Stdout>>> {
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -37,34 +64,117 @@ fn main() -> Result<()> {
     // 解析命令行参数
     let args = Args::parse();
 
-    // 构建Redis连接URL
-    let redis_url = if let Some(url) = args.url {
-        url
-    } else {
-        format!(
-            "redis://{}:{}@{}:{}/{}",
-            args.password.as_deref().unwrap_or(""),
-            args.password.as_deref().unwrap_or(""),
-            args.host,
-            args.port,
-            args.db
-        )
+    // 加载配置文件, 命令行的解码规则优先于配置文件
+    let config = Config::load(args.config.as_deref())?;
+    let mut rules = args.decoder;
+    rules.extend(args.proto.iter().map(ProtobufRule::to_decoder_rule));
+    rules.extend(config.decoders);
+    rules.extend(
+        config
+            .protobuf
+            .messages
+            .iter()
+            .map(ProtobufRule::to_decoder_rule),
+    );
+    let mut decoders = DecoderRegistry::new();
+    if let Some(path) = args.proto_descriptor.or(config.protobuf.descriptor_set) {
+        let pool = protobuf::load_descriptor_set(&path)?;
+        protobuf::register_decoders(&mut decoders, &pool, &rules)?;
+    }
+    decoders.add_rules(rules)?;
+    let mut decompressor = Decompressor::default();
+    decompressor.add_rules(args.compression)?;
+    decompressor.add_rules(config.compression)?;
+
+    // 命令行的连接参数, 选择档案时覆盖档案中的对应字段
+    let cli = Profile {
+        url: args.url,
+        socket: args.socket,
+        host: args.host,
+        port: args.port,
+        user: args.user,
+        db: args.db,
+        tls: args.tls,
+        cacert: args.cacert,
+        cert: args.cert,
+        key: args.key,
+        insecure: args.insecure,
+        ..Profile::default()
+    };
+    let password = match args.password {
+        Some(password) => Some(password),
+        None if args.ask_password => Some(connection::prompt_password("Password: ")?),
+        None => None,
     };
 
-    // 初始化终端
-    let mut terminal = init_terminal()?;
     let mut app = App::new();
+    app.set_scan_count(args.scan_count);
+    app.set_decoders(decoders);
+    app.set_decompressor(decompressor);
+    app.set_unlink(args.unlink);
+    let refresh = args
+        .refresh
+        .or(config.refresh)
+        .unwrap_or_else(|| app::DEFAULT_REFRESH.to_string());
+    app.set_refresh_interval(match refresh.as_str() {
+        "off" | "0" => None,
+        interval => Some(Duration::from_millis(ttl::parse_duration(interval)? as u64)),
+    });
+    if let Some(delimiter) = args.delimiter.or(config.delimiter) {
+        app.set_tree_delimiter(&delimiter);
+    }
 
-    // 尝试默认连接
-    if let Err(e) = app.connect_redis(&redis_url) {
-        app.set_status(format!("Connection failed: {} URL: {}", e, redis_url));
+    // 指定档案时直接连接; 有档案且命令行未指定服务器时在界面中选择
+    if let Some(name) = &args.profile {
+        let profile = config.profiles.get(name).ok_or_else(|| {
+            let names: Vec<&str> = config.profiles.keys().map(String::as_str).collect();
+            anyhow!(
+                "Unknown profile \"{}\", available: {}",
+                name,
+                names.join(", ")
+            )
+        })?;
+        connect(&mut app, Some(name), &cli.merged(profile), password.clone());
+    } else if !config.profiles.is_empty() && !cli.has_target() {
+        app.set_profile_picker(
+            config
+                .profiles
+                .iter()
+                .map(|(name, profile)| (name.clone(), cli.merged(profile)))
+                .collect(),
+        );
+    } else {
+        connect(&mut app, None, &cli, password.clone());
     }
 
+    // 初始化终端
+    let mut terminal = init_terminal()?;
     loop {
         if let Ok(true) = app.run(&mut terminal) {
             restore_terminal(&mut terminal)?;
             return Ok(());
         }
+        // 挂起界面连接选中的档案, 密码命令可能需要在终端中交互
+        if let Some((name, profile)) = app.take_connect_request() {
+            restore_terminal(&mut terminal)?;
+            connect(&mut app, Some(&name), &profile, password.clone());
+            terminal = init_terminal()?;
+        }
+        // 挂起界面, 在外部编辑器中编辑值
+        if let Some(request) = app.take_edit_request() {
+            restore_terminal(&mut terminal)?;
+            let edited = editor::edit(&request.value, request.suffix);
+            terminal = init_terminal()?;
+            app.finish_edit(request, edited);
+        }
+    }
+}
+
+// 读取密码并连接, 失败时在状态栏显示原因
+fn connect(app: &mut App, name: Option<&str>, profile: &Profile, password: Option<String>) {
+    match connection::password(profile, password) {
+        Ok(password) => app.connect(name, profile, password),
+        Err(e) => app.set_status(format!("Connection failed: {}", e)),
     }
 }
 
@@ -72,23 +182,95 @@ fn main() -> Result<()> {
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// Redis服务器地址
-    #[arg(long, default_value = "127.0.0.1")]
-    host: String,
+    /// Redis服务器地址 (默认 127.0.0.1)
+    #[arg(long)]
+    host: Option<String>,
 
-    /// Redis服务器端口
-    #[arg(long, default_value_t = 6379)]
-    port: u16,
+    /// Redis服务器端口 (默认 6379)
+    #[arg(long)]
+    port: Option<u16>,
 
-    /// Redis密码
+    /// Redis 6+ ACL用户名
+    #[arg(long)]
+    user: Option<String>,
+
+    /// Redis密码, 未指定时读取环境变量REDISCLI_AUTH
     #[arg(long)]
     password: Option<String>,
 
-    /// Redis数据库编号
-    #[arg(short, long, default_value_t = 0)]
-    db: u8,
+    /// 启动前在终端输入密码 (不回显)
+    #[arg(long, conflicts_with_all = ["password", "url"])]
+    ask_password: bool,
+
+    /// Redis数据库编号 (默认 0)
+    #[arg(short, long)]
+    db: Option<i64>,
+
+    /// 使用配置文件中的连接档案, 命令行的连接参数优先
+    #[arg(long, value_name = "NAME")]
+    profile: Option<String>,
+
+    /// 通过Unix套接字连接, 如 /var/run/redis/redis.sock
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["url", "tls", "cacert", "cert", "key", "insecure"])]
+    socket: Option<PathBuf>,
 
     /// Redis连接URL (优先于单独的主机/端口参数)
     #[arg(short, long)]
     url: Option<String>,
+
+    /// 使用TLS连接 (rediss://)
+    #[arg(long)]
+    tls: bool,
+
+    /// 校验服务端证书的CA证书 (PEM), 代替系统证书
+    #[arg(long, value_name = "FILE")]
+    cacert: Option<PathBuf>,
+
+    /// mTLS客户端证书 (PEM), 需同时指定--key
+    #[arg(long, value_name = "FILE")]
+    cert: Option<PathBuf>,
+
+    /// mTLS客户端私钥 (PEM)
+    #[arg(long, value_name = "FILE")]
+    key: Option<PathBuf>,
+
+    /// 不校验服务端证书 (仅用于测试)
+    #[arg(long)]
+    insecure: bool,
+
+    /// 每次SCAN的COUNT提示值
+    #[arg(long, default_value_t = app::DEFAULT_SCAN_COUNT)]
+    scan_count: usize,
+
+    /// 配置文件路径 (默认 <配置目录>/rdsview/config.toml)
+    #[arg(long)]
+    config: Option<PathBuf>,
+
+    /// 为匹配的键指定解码器: <pattern>=<json|msgpack|cbor|bson|raw>, 可重复
+    #[arg(long, value_name = "PATTERN=DECODER")]
+    decoder: Vec<DecoderRule>,
+
+    /// protobuf描述符集合文件 (protoc --descriptor_set_out生成)
+    #[arg(long, value_name = "FILE")]
+    proto_descriptor: Option<PathBuf>,
+
+    /// 以protobuf消息类型解码匹配的键: <pattern>=<package.Message>, 可重复
+    #[arg(long, value_name = "PATTERN=MESSAGE")]
+    proto: Vec<ProtobufRule>,
+
+    /// 为匹配的键指定压缩格式: <pattern>=<gzip|zlib|zstd|lz4|lz4-block|snappy|snappy-raw|none>, 可重复
+    #[arg(long, value_name = "PATTERN=CODEC")]
+    compression: Vec<CompressionRule>,
+
+    /// 树视图中的命名空间分隔符 (默认 ":")
+    #[arg(long)]
+    delimiter: Option<String>,
+
+    /// 删除键时使用UNLINK代替DEL (服务端异步释放内存)
+    #[arg(long)]
+    unlink: bool,
+
+    /// 自动刷新选中键的间隔, 如 5s、1m, off 关闭 (默认 off)
+    #[arg(long, value_name = "DURATION")]
+    refresh: Option<String>,
 }
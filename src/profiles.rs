@@ -0,0 +1,94 @@
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Cell, Clear, Row, Table, TableState},
+    Frame,
+};
+
+use crate::config::Profile;
+use crate::connection::{self, DEFAULT_HOST, DEFAULT_PORT};
+
+// 启动时选择连接档案的界面
+pub struct ProfilePicker {
+    profiles: Vec<(String, Profile)>,
+    state: TableState,
+}
+
+impl ProfilePicker {
+    pub fn new(profiles: Vec<(String, Profile)>) -> Self {
+        let mut state = TableState::default();
+        state.select(Some(0));
+        Self { profiles, state }
+    }
+
+    pub fn select_previous(&mut self) {
+        self.state.select_previous();
+    }
+
+    pub fn select_next(&mut self) {
+        let last = self.profiles.len().saturating_sub(1);
+        let next = self
+            .state
+            .selected()
+            .map_or(0, |index| (index + 1).min(last));
+        self.state.select(Some(next));
+    }
+
+    // 选中的档案名和档案
+    pub fn selected(&self) -> Option<(String, Profile)> {
+        self.profiles.get(self.state.selected()?).cloned()
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        frame.render_widget(Clear, area);
+        let header = Row::new(["Profile", "Server", "User", "DB", "TLS"])
+            .style(Style::default().add_modifier(Modifier::BOLD));
+        let rows = self.profiles.iter().map(|(name, profile)| {
+            let color = profile.color().ok().flatten().unwrap_or(Color::Reset);
+            Row::new([
+                Cell::from(name.clone()).style(Style::default().fg(color)),
+                Cell::from(server(profile)),
+                Cell::from(profile.user.clone().unwrap_or_default()),
+                // URL中自带数据库编号
+                Cell::from(match (profile.db, &profile.url) {
+                    (Some(db), _) => db.to_string(),
+                    (None, Some(_)) => String::new(),
+                    (None, None) => "0".to_string(),
+                }),
+                Cell::from(if profile.uses_tls() { "yes" } else { "" }),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Fill(1),
+                Constraint::Fill(2),
+                Constraint::Fill(1),
+                Constraint::Length(4),
+                Constraint::Length(4),
+            ],
+        )
+        .header(header)
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol(">")
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Connect to (Enter: Connect, Q/Esc: Quit)"),
+        );
+        frame.render_stateful_widget(table, area, &mut self.state);
+    }
+}
+
+// 档案的连接目标, URL中的密码已隐藏
+fn server(profile: &Profile) -> String {
+    match (&profile.url, &profile.socket) {
+        (Some(url), _) => connection::redact_url(url),
+        (None, Some(socket)) => socket.display().to_string(),
+        (None, None) => format!(
+            "{}:{}",
+            profile.host.as_deref().unwrap_or(DEFAULT_HOST),
+            profile.port.unwrap_or(DEFAULT_PORT)
+        ),
+    }
+}
@@ -0,0 +1,84 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+// 输入框的用途, 提交时据此分发
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum PromptKind {
+    SetFilter,
+    SetIsMember,
+    ZSetScoreRange,
+    ZSetLexRange,
+    HexJump,
+    Expire,
+    HashNewField,
+    // 设置字段的值, 新增字段时为第二步
+    HashFieldValue { field: Vec<u8> },
+    HashRenameField { field: Vec<u8> },
+    RenameKey,
+    CopyKey,
+    MoveKey,
+}
+
+// 单行输入弹窗
+pub struct Prompt {
+    pub kind: PromptKind,
+    pub title: String,
+    pub input: String,
+}
+
+impl Prompt {
+    pub fn new(kind: PromptKind, title: &str) -> Self {
+        Self {
+            kind,
+            title: title.to_string(),
+            input: String::new(),
+        }
+    }
+
+    // 带初始内容的输入框, 用于修改已有的值
+    pub fn with_input(kind: PromptKind, title: &str, input: &str) -> Self {
+        Self {
+            kind,
+            title: title.to_string(),
+            input: input.to_string(),
+        }
+    }
+
+    // 在area中央渲染输入框
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let popup = centered_rect(60, 3, area);
+        frame.render_widget(Clear, popup);
+        let input = Paragraph::new(format!("{}_", self.input))
+            .style(Style::default().fg(Color::Yellow))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!("{} (Enter: OK, Esc: Cancel)", self.title)),
+            );
+        frame.render_widget(input, popup);
+    }
+}
+
+// 计算居中弹窗区域, 宽度为百分比, 高度为行数
+pub fn centered_rect(percent_x: u16, height: u16, area: Rect) -> Rect {
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Fill(1),
+            Constraint::Length(height),
+            Constraint::Fill(1),
+        ])
+        .split(area);
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(vertical[1])[1]
+}
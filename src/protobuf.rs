@@ -0,0 +1,71 @@
+use anyhow::{anyhow, Context, Result};
+use prost_reflect::{DescriptorPool, DynamicMessage, MessageDescriptor};
+use serde_json::Value;
+use std::{collections::BTreeSet, fs, path::Path};
+
+use crate::config::DecoderRule;
+use crate::decode::{DecoderRegistry, ValueDecoder};
+
+// 解码器名称前缀, 如 protobuf:acme.User
+pub const PROTOBUF_PREFIX: &str = "protobuf:";
+
+// 读取protoc --descriptor_set_out生成的FileDescriptorSet
+pub fn load_descriptor_set(path: &Path) -> Result<DescriptorPool> {
+    let bytes = fs::read(path)
+        .with_context(|| format!("Failed to read descriptor set {}", path.display()))?;
+    DescriptorPool::decode(bytes.as_slice())
+        .with_context(|| format!("Failed to parse descriptor set {}", path.display()))
+}
+
+// 为规则中引用的每个protobuf消息类型注册解码器
+pub fn register_decoders(
+    registry: &mut DecoderRegistry,
+    pool: &DescriptorPool,
+    rules: &[DecoderRule],
+) -> Result<()> {
+    let messages: BTreeSet<&str> = rules
+        .iter()
+        .filter_map(|rule| rule.decoder.strip_prefix(PROTOBUF_PREFIX))
+        .collect();
+    for message in messages {
+        registry.register(Box::new(ProtobufDecoder::new(pool, message)?));
+    }
+    Ok(())
+}
+
+// 按指定消息类型解码protobuf
+pub struct ProtobufDecoder {
+    name: String,
+    message: MessageDescriptor,
+}
+
+impl ProtobufDecoder {
+    pub fn new(pool: &DescriptorPool, message_name: &str) -> Result<Self> {
+        let message = pool.get_message_by_name(message_name).ok_or_else(|| {
+            anyhow!(
+                "Message type \"{}\" not found in descriptor set",
+                message_name
+            )
+        })?;
+        Ok(Self {
+            name: format!("{}{}", PROTOBUF_PREFIX, message_name),
+            message,
+        })
+    }
+}
+
+impl ValueDecoder for ProtobufDecoder {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    // protobuf没有可识别的标记, 只按键模式使用
+    fn sniff(&self, _bytes: &[u8]) -> bool {
+        false
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Option<Value> {
+        let message = DynamicMessage::decode(self.message.clone(), bytes).ok()?;
+        serde_json::to_value(&message).ok()
+    }
+}
@@ -0,0 +1,74 @@
+use anyhow::Result;
+use redis::Connection;
+use std::collections::HashSet;
+
+// SCAN游标分页加载键, 避免KEYS *阻塞服务器
+pub struct KeyScanner {
+    cursor: u64,
+    finished: bool,
+    count: usize,
+    pattern: Option<String>,
+    key_type: Option<String>,
+    // SCAN可能重复返回同一个键, 记录已返回过的键用于去重
+    seen: HashSet<Vec<u8>>,
+}
+
+impl KeyScanner {
+    pub fn new(count: usize) -> Self {
+        Self {
+            cursor: 0,
+            finished: false,
+            count,
+            pattern: None,
+            key_type: None,
+            seen: HashSet::new(),
+        }
+    }
+
+    // 服务端glob匹配 (SCAN MATCH)
+    pub fn with_match(mut self, pattern: &str) -> Self {
+        self.pattern = Some(pattern.to_string());
+        self
+    }
+
+    // 服务端类型过滤 (SCAN TYPE, Redis 6.0+)
+    pub fn with_type(mut self, key_type: Option<&str>) -> Self {
+        self.key_type = key_type.map(str::to_string);
+        self
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    // 获取下一页键, 游标回到0时表示遍历结束
+    pub fn next_page(&mut self, conn: &mut Connection) -> Result<Vec<Vec<u8>>> {
+        if self.finished {
+            return Ok(Vec::new());
+        }
+        let mut cmd = redis::cmd("SCAN");
+        cmd.arg(self.cursor);
+        if let Some(pattern) = &self.pattern {
+            cmd.arg("MATCH").arg(pattern);
+        }
+        if let Some(key_type) = &self.key_type {
+            cmd.arg("TYPE").arg(key_type);
+        }
+        let (cursor, mut keys): (u64, Vec<Vec<u8>>) =
+            cmd.arg("COUNT").arg(self.count).query(conn)?;
+        self.cursor = cursor;
+        self.finished = cursor == 0;
+        keys.retain(|key| self.seen.insert(key.clone()));
+        Ok(keys)
+    }
+
+    // 记录在SCAN之外加入列表的键(新建、复制、重命名), 返回该键此前是否未出现过
+    pub fn insert(&mut self, key: &[u8]) -> bool {
+        self.seen.insert(key.to_vec())
+    }
+
+    // 键被删除或改名后忘记它, 之后重新出现时可以再次加入列表
+    pub fn remove(&mut self, key: &[u8]) {
+        self.seen.remove(key);
+    }
+}
@@ -0,0 +1,317 @@
+use anyhow::Result;
+use redis::{Connection, Value};
+
+use crate::display::escape_bytes;
+
+// 每次XRANGE加载的条目数
+const STREAM_PAGE_SIZE: usize = 100;
+// XPENDING最多显示的条目数
+const PENDING_COUNT: usize = 100;
+
+// 流详情的子页签
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum StreamTab {
+    Entries,
+    Groups,
+    Consumers,
+    Pending,
+}
+
+impl StreamTab {
+    pub const ALL: [StreamTab; 4] = [
+        StreamTab::Entries,
+        StreamTab::Groups,
+        StreamTab::Consumers,
+        StreamTab::Pending,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            StreamTab::Entries => "Entries",
+            StreamTab::Groups => "Groups",
+            StreamTab::Consumers => "Consumers",
+            StreamTab::Pending => "Pending",
+        }
+    }
+
+    pub fn index(&self) -> usize {
+        Self::ALL.iter().position(|tab| tab == self).unwrap_or(0)
+    }
+}
+
+#[derive(PartialEq)]
+pub struct StreamEntry {
+    pub id: String,
+    pub fields: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+// XINFO/XPENDING的结果, 每行是有序的(字段, 值)
+pub type InfoRows = Vec<Vec<(String, String)>>;
+
+// 以XRANGE/XREVRANGE分页加载的流, 以及消费组信息
+pub struct StreamView {
+    pub len: usize,
+    pub entries: Vec<StreamEntry>,
+    pub descending: bool,
+    pub tab: StreamTab,
+    pub groups: InfoRows,
+    pub group_index: usize,
+    pub consumers: InfoRows,
+    pub pending: InfoRows,
+    finished: bool,
+}
+
+impl StreamView {
+    // 获取流长度、消费组并加载第一页条目
+    pub fn load(conn: &mut Connection, key: &[u8], descending: bool) -> Result<Self> {
+        let len: usize = redis::cmd("XLEN").arg(key).query(conn)?;
+        let mut stream = Self {
+            len,
+            entries: Vec::new(),
+            descending,
+            tab: StreamTab::Entries,
+            groups: Vec::new(),
+            group_index: 0,
+            consumers: Vec::new(),
+            pending: Vec::new(),
+            finished: false,
+        };
+        stream.load_more(conn, key)?;
+        stream.load_groups(conn, key)?;
+        Ok(stream)
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.finished
+    }
+
+    // 从上一页最后一个ID之后继续加载
+    pub fn load_more(&mut self, conn: &mut Connection, key: &[u8]) -> Result<()> {
+        if self.finished {
+            return Ok(());
+        }
+        let last_id = self.entries.last().map(|entry| entry.id.as_str());
+        let reply: Value = if self.descending {
+            let end = match last_id {
+                Some(id) => match previous_id(id) {
+                    Some(end) => end,
+                    None => {
+                        self.finished = true;
+                        return Ok(());
+                    }
+                },
+                None => "+".to_string(),
+            };
+            redis::cmd("XREVRANGE")
+                .arg(key)
+                .arg(end)
+                .arg("-")
+                .arg("COUNT")
+                .arg(STREAM_PAGE_SIZE)
+                .query(conn)?
+        } else {
+            let start = match last_id {
+                Some(id) => match next_id(id) {
+                    Some(start) => start,
+                    None => {
+                        self.finished = true;
+                        return Ok(());
+                    }
+                },
+                None => "-".to_string(),
+            };
+            redis::cmd("XRANGE")
+                .arg(key)
+                .arg(start)
+                .arg("+")
+                .arg("COUNT")
+                .arg(STREAM_PAGE_SIZE)
+                .query(conn)?
+        };
+        let page = parse_entries(&reply);
+        self.finished = page.len() < STREAM_PAGE_SIZE;
+        self.entries.extend(page);
+        Ok(())
+    }
+
+    pub fn selected_group(&self) -> Option<&str> {
+        self.groups.get(self.group_index).and_then(|group| {
+            group
+                .iter()
+                .find(|(field, _)| field == "name")
+                .map(|(_, name)| name.as_str())
+        })
+    }
+
+    // XINFO GROUPS
+    pub fn load_groups(&mut self, conn: &mut Connection, key: &[u8]) -> Result<()> {
+        let reply: Value = redis::cmd("XINFO").arg("GROUPS").arg(key).query(conn)?;
+        self.groups = parse_info_rows(&reply);
+        if self.group_index >= self.groups.len() {
+            self.group_index = 0;
+        }
+        Ok(())
+    }
+
+    // 加载当前页签需要的数据, 消费者和待处理列表针对选中的消费组
+    pub fn load_tab(&mut self, conn: &mut Connection, key: &[u8]) -> Result<()> {
+        match self.tab {
+            StreamTab::Entries => {}
+            StreamTab::Groups => self.load_groups(conn, key)?,
+            StreamTab::Consumers => {
+                self.consumers = match self.selected_group() {
+                    Some(group) => {
+                        let reply: Value = redis::cmd("XINFO")
+                            .arg("CONSUMERS")
+                            .arg(key)
+                            .arg(group)
+                            .query(conn)?;
+                        parse_info_rows(&reply)
+                    }
+                    None => Vec::new(),
+                };
+            }
+            StreamTab::Pending => {
+                self.pending = match self.selected_group() {
+                    Some(group) => {
+                        let reply: Value = redis::cmd("XPENDING")
+                            .arg(key)
+                            .arg(group)
+                            .arg("-")
+                            .arg("+")
+                            .arg(PENDING_COUNT)
+                            .query(conn)?;
+                        parse_pending(&reply)
+                    }
+                    None => Vec::new(),
+                };
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse_id(id: &str) -> Option<(u64, u64)> {
+    let (ms, seq) = id.split_once('-')?;
+    Some((ms.parse().ok()?, seq.parse().ok()?))
+}
+
+// 紧随id之后的最小ID, 用于兼容不支持"(id"语法的Redis版本
+fn next_id(id: &str) -> Option<String> {
+    let (ms, seq) = parse_id(id)?;
+    match seq.checked_add(1) {
+        Some(seq) => Some(format!("{}-{}", ms, seq)),
+        None => Some(format!("{}-0", ms.checked_add(1)?)),
+    }
+}
+
+// 紧邻id之前的最大ID
+fn previous_id(id: &str) -> Option<String> {
+    let (ms, seq) = parse_id(id)?;
+    match seq.checked_sub(1) {
+        Some(seq) => Some(format!("{}-{}", ms, seq)),
+        None => Some(format!("{}-{}", ms.checked_sub(1)?, u64::MAX)),
+    }
+}
+
+fn value_to_bytes(value: &Value) -> Vec<u8> {
+    match value {
+        Value::BulkString(data) => data.clone(),
+        value => value_to_string(value).into_bytes(),
+    }
+}
+
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::Nil => "(nil)".to_string(),
+        Value::Int(i) => i.to_string(),
+        Value::BulkString(data) => escape_bytes(data),
+        Value::Array(items) => items
+            .iter()
+            .map(value_to_string)
+            .collect::<Vec<_>>()
+            .join(" "),
+        Value::SimpleString(status) => status.clone(),
+        Value::Okay => "OK".to_string(),
+        Value::Double(d) => d.to_string(),
+        Value::Boolean(b) => b.to_string(),
+        Value::VerbatimString { text, .. } => text.clone(),
+        // 其余RESP3类型在RESP2连接中不会出现
+        value => format!("{:?}", value),
+    }
+}
+
+// [[id, [field, value, ...]], ...]
+fn parse_entries(reply: &Value) -> Vec<StreamEntry> {
+    let Value::Array(items) = reply else {
+        return Vec::new();
+    };
+    items
+        .iter()
+        .filter_map(|item| match item {
+            Value::Array(parts) if parts.len() == 2 => {
+                let fields = match &parts[1] {
+                    Value::Array(fields) => fields
+                        .chunks(2)
+                        .map(|pair| {
+                            (
+                                value_to_bytes(&pair[0]),
+                                pair.get(1).map(value_to_bytes).unwrap_or_default(),
+                            )
+                        })
+                        .collect(),
+                    _ => Vec::new(),
+                };
+                Some(StreamEntry {
+                    id: value_to_string(&parts[0]),
+                    fields,
+                })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+// XINFO GROUPS/CONSUMERS返回的扁平键值数组列表
+fn parse_info_rows(reply: &Value) -> InfoRows {
+    let Value::Array(items) = reply else {
+        return Vec::new();
+    };
+    items
+        .iter()
+        .filter_map(|item| match item {
+            Value::Array(fields) => Some(
+                fields
+                    .chunks(2)
+                    .map(|pair| {
+                        (
+                            value_to_string(&pair[0]),
+                            pair.get(1).map(value_to_string).unwrap_or_default(),
+                        )
+                    })
+                    .collect(),
+            ),
+            _ => None,
+        })
+        .collect()
+}
+
+// XPENDING扩展格式: [[id, consumer, idle, deliveries], ...]
+fn parse_pending(reply: &Value) -> InfoRows {
+    let Value::Array(items) = reply else {
+        return Vec::new();
+    };
+    items
+        .iter()
+        .filter_map(|item| match item {
+            Value::Array(parts) if parts.len() == 4 => Some(
+                ["id", "consumer", "idle (ms)", "deliveries"]
+                    .iter()
+                    .zip(parts)
+                    .map(|(name, value)| (name.to_string(), value_to_string(value)))
+                    .collect(),
+            ),
+            _ => None,
+        })
+        .collect()
+}
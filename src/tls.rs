@@ -0,0 +1,48 @@
+use anyhow::{bail, Context, Result};
+use redis::{Client, ClientTlsConfig, TlsCertificates};
+use std::{fs, path::Path, path::PathBuf};
+
+// TLS证书文件, 均为PEM格式
+#[derive(Default)]
+pub struct TlsFiles {
+    // 自定义CA, 指定后不再使用系统证书
+    pub ca_cert: Option<PathBuf>,
+    // mTLS客户端证书和私钥, 需同时指定
+    pub cert: Option<PathBuf>,
+    pub key: Option<PathBuf>,
+}
+
+impl TlsFiles {
+    pub fn is_empty(&self) -> bool {
+        self.ca_cert.is_none() && self.cert.is_none() && self.key.is_none()
+    }
+
+    // 读取证书文件
+    fn certificates(&self) -> Result<TlsCertificates> {
+        let client_tls = match (&self.cert, &self.key) {
+            (Some(cert), Some(key)) => Some(ClientTlsConfig {
+                client_cert: read_pem(cert)?,
+                client_key: read_pem(key)?,
+            }),
+            (None, None) => None,
+            _ => bail!("A client certificate and key must be given together"),
+        };
+        let root_cert = self.ca_cert.as_deref().map(read_pem).transpose()?;
+        Ok(TlsCertificates {
+            client_tls,
+            root_cert,
+        })
+    }
+}
+
+// 创建客户端, 指定了证书文件时加载到rediss://连接中
+pub fn open_client(url: &str, files: &TlsFiles) -> Result<Client> {
+    if files.is_empty() {
+        return Ok(Client::open(url)?);
+    }
+    Ok(Client::build_with_tls(url, files.certificates()?)?)
+}
+
+fn read_pem(path: &Path) -> Result<Vec<u8>> {
+    fs::read(path).with_context(|| format!("Failed to read {}", path.display()))
+}
@@ -0,0 +1,165 @@
+use std::collections::{BTreeMap, HashSet};
+
+// 默认命名空间分隔符
+pub const DEFAULT_DELIMITER: &str = ":";
+
+// 树视图中的一行: 命名空间文件夹或键
+pub enum TreeRow {
+    Folder {
+        // 含末尾分隔符的完整前缀, 如 user:session:
+        prefix: Vec<u8>,
+        name: Vec<u8>,
+        depth: usize,
+        count: usize,
+        expanded: bool,
+    },
+    Key {
+        key: Vec<u8>,
+        name: Vec<u8>,
+        depth: usize,
+    },
+}
+
+impl TreeRow {
+    pub fn depth(&self) -> usize {
+        match self {
+            TreeRow::Folder { depth, .. } | TreeRow::Key { depth, .. } => *depth,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Node {
+    children: BTreeMap<Vec<u8>, Node>,
+    // 恰好等于该路径的键, 如同时存在 a:b 和 a:b:c
+    key: Option<Vec<u8>>,
+    count: usize,
+}
+
+// 按分隔符把键组织成命名空间树, 展开后平铺为可显示的行
+pub struct KeyTree {
+    delimiter: Vec<u8>,
+    expanded: HashSet<Vec<u8>>,
+    rows: Vec<TreeRow>,
+}
+
+impl KeyTree {
+    pub fn new(delimiter: &str) -> Self {
+        Self {
+            delimiter: delimiter.as_bytes().to_vec(),
+            expanded: HashSet::new(),
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn rows(&self) -> &[TreeRow] {
+        &self.rows
+    }
+
+    // 重建行列表, expand_all时忽略折叠状态(如搜索结果)
+    pub fn rebuild(&mut self, keys: &[Vec<u8>], expand_all: bool) {
+        let mut root = Node::default();
+        for key in keys {
+            let mut node = &mut root;
+            node.count += 1;
+            for part in split(key, &self.delimiter) {
+                node = node.children.entry(part.to_vec()).or_default();
+                node.count += 1;
+            }
+            node.key = Some(key.clone());
+        }
+        self.rows.clear();
+        let mut prefix = Vec::new();
+        self.flatten(&root, &mut prefix, 0, expand_all);
+    }
+
+    // 文件夹在前, 键在后, 各自按名称排序
+    fn flatten(&mut self, node: &Node, prefix: &mut Vec<u8>, depth: usize, expand_all: bool) {
+        for (name, child) in &node.children {
+            if child.children.is_empty() {
+                continue;
+            }
+            let len = prefix.len();
+            prefix.extend_from_slice(name);
+            prefix.extend_from_slice(&self.delimiter);
+            let expanded = expand_all || self.expanded.contains(prefix.as_slice());
+            self.rows.push(TreeRow::Folder {
+                prefix: prefix.clone(),
+                name: name.clone(),
+                depth,
+                count: child.count - usize::from(child.key.is_some()),
+                expanded,
+            });
+            if expanded {
+                self.flatten(child, prefix, depth + 1, expand_all);
+            }
+            prefix.truncate(len);
+        }
+        for (name, child) in &node.children {
+            if let Some(key) = &child.key {
+                self.rows.push(TreeRow::Key {
+                    key: key.clone(),
+                    name: name.clone(),
+                    depth,
+                });
+            }
+        }
+    }
+
+    // 展开或折叠文件夹
+    pub fn set_expanded(&mut self, prefix: &[u8], expanded: bool) {
+        if expanded {
+            self.expanded.insert(prefix.to_vec());
+        } else {
+            self.expanded.remove(prefix);
+        }
+    }
+
+    // 展开键的所有上级文件夹, 使其在树中可见
+    pub fn reveal(&mut self, key: &[u8]) {
+        let parts = split(key, &self.delimiter);
+        let mut prefix = Vec::new();
+        for part in &parts[..parts.len() - 1] {
+            prefix.extend_from_slice(part);
+            prefix.extend_from_slice(&self.delimiter);
+            self.expanded.insert(prefix.clone());
+        }
+    }
+
+    // 行所在的上级文件夹的行号
+    pub fn parent_of(&self, index: usize) -> Option<usize> {
+        let depth = self.rows.get(index)?.depth();
+        self.rows[..index]
+            .iter()
+            .rposition(|row| row.depth() + 1 == depth)
+    }
+
+    // 查找键或文件夹前缀对应的行号
+    pub fn position(&self, target: &[u8], is_folder: bool) -> Option<usize> {
+        self.rows.iter().position(|row| match row {
+            TreeRow::Folder { prefix, .. } => is_folder && prefix == target,
+            TreeRow::Key { key, .. } => !is_folder && key == target,
+        })
+    }
+}
+
+// 空分隔符时不拆分
+fn split<'a>(key: &'a [u8], delimiter: &'a [u8]) -> Vec<&'a [u8]> {
+    if delimiter.is_empty() {
+        return vec![key];
+    }
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+    while i + delimiter.len() <= key.len() {
+        if &key[i..i + delimiter.len()] == delimiter {
+            parts.push(&key[start..i]);
+            i += delimiter.len();
+            start = i;
+        } else {
+            i += 1;
+        }
+    }
+    parts.push(&key[start..]);
+    parts
+}
@@ -0,0 +1,248 @@
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone};
+
+// 过期时间设置
+pub enum Expiry {
+    Persist,
+    // 相对时长, 毫秒, 恒为正
+    In(i64),
+    // 绝对时间, unix毫秒, 恒为正
+    At(i64),
+}
+
+impl Expiry {
+    // 支持: 时长如 1h30m、90s、500ms; @unix秒; RFC3339或本地时间 2024-01-02 15:04[:05];
+    // 空、persist 或 -1 表示移除过期时间
+    pub fn parse(input: &str) -> Result<Self> {
+        let input = input.trim();
+        if input.is_empty() || input == "-1" || input.eq_ignore_ascii_case("persist") {
+            return Ok(Expiry::Persist);
+        }
+        if let Some(timestamp) = input.strip_prefix('@') {
+            let seconds: i64 = timestamp
+                .parse()
+                .map_err(|_| anyhow!("Invalid unix timestamp \"{}\"", timestamp))?;
+            let ms = seconds
+                .checked_mul(1000)
+                .ok_or_else(|| anyhow!("Unix timestamp \"{}\" is out of range", timestamp))?;
+            return future(ms, input);
+        }
+        if input.contains('-') {
+            return future(parse_datetime(input)?.timestamp_millis(), input);
+        }
+        parse_duration(input).map(Expiry::In)
+    }
+
+    // 整秒时使用EXPIRE/EXPIREAT, 否则使用毫秒精度的PEXPIRE/PEXPIREAT
+    pub fn command(&self, key: &[u8]) -> redis::Cmd {
+        let (name, arg) = match *self {
+            Expiry::Persist => ("PERSIST", None),
+            Expiry::In(ms) if ms % 1000 == 0 => ("EXPIRE", Some(ms / 1000)),
+            Expiry::In(ms) => ("PEXPIRE", Some(ms)),
+            Expiry::At(ms) if ms % 1000 == 0 => ("EXPIREAT", Some(ms / 1000)),
+            Expiry::At(ms) => ("PEXPIREAT", Some(ms)),
+        };
+        let mut cmd = redis::cmd(name);
+        cmd.arg(key).arg(arg);
+        cmd
+    }
+}
+
+// 过去的时间会让EXPIREAT立即删除键, 只接受将来的时间
+fn future(ms: i64, input: &str) -> Result<Expiry> {
+    if ms <= Local::now().timestamp_millis() {
+        bail!("Time \"{}\" is in the past", input);
+    }
+    Ok(Expiry::At(ms))
+}
+
+// 解析时长, 单位 w/d/h/m/s/ms, 不带单位的纯数字按秒计, 返回毫秒
+// 超出i64毫秒范围时报错, 避免溢出成负数让Redis直接删除键
+pub fn parse_duration(input: &str) -> Result<i64> {
+    let input: String = input.chars().filter(|c| !c.is_whitespace()).collect();
+    let invalid = || anyhow!("Invalid duration \"{}\", expected e.g. 1h30m", input);
+    let too_long = || anyhow!("Duration \"{}\" is too long", input);
+    let mut total: i64 = 0;
+    let mut rest = input.as_str();
+    if !rest.is_empty() && rest.bytes().all(|b| b.is_ascii_digit()) {
+        total = rest
+            .parse::<i64>()
+            .ok()
+            .and_then(|seconds| seconds.checked_mul(1000))
+            .ok_or_else(too_long)?;
+        rest = "";
+    }
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .ok_or_else(invalid)?;
+        if digits == 0 {
+            return Err(invalid());
+        }
+        let value: i64 = rest[..digits].parse().map_err(|_| too_long())?;
+        rest = &rest[digits..];
+        let unit_len = rest
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let unit = match &rest[..unit_len] {
+            "ms" => 1,
+            "s" => 1000,
+            "m" => 60 * 1000,
+            "h" => 60 * 60 * 1000,
+            "d" => 24 * 60 * 60 * 1000,
+            "w" => 7 * 24 * 60 * 60 * 1000,
+            _ => return Err(invalid()),
+        };
+        total = value
+            .checked_mul(unit)
+            .and_then(|ms| total.checked_add(ms))
+            .ok_or_else(too_long)?;
+        rest = &rest[unit_len..];
+    }
+    if total <= 0 {
+        bail!("Duration must be greater than zero");
+    }
+    Ok(total)
+}
+
+fn parse_datetime(input: &str) -> Result<DateTime<Local>> {
+    if let Ok(at) = DateTime::parse_from_rfc3339(input) {
+        return Ok(at.with_timezone(&Local));
+    }
+    let naive = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M:%S"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(input, format).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(input, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })
+        .ok_or_else(|| anyhow!("Invalid time \"{}\"", input))?;
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .ok_or_else(|| anyhow!("Invalid local time \"{}\"", input))
+}
+
+// 把毫秒格式化为 1d 2h 3m 4.567s
+pub fn format_duration(ms: i64) -> String {
+    let (days, rest) = (ms / 86_400_000, ms % 86_400_000);
+    let (hours, rest) = (rest / 3_600_000, rest % 3_600_000);
+    let (minutes, rest) = (rest / 60_000, rest % 60_000);
+    let mut parts = Vec::new();
+    if days > 0 {
+        parts.push(format!("{}d", days));
+    }
+    if days > 0 || hours > 0 {
+        parts.push(format!("{}h", hours));
+    }
+    if days > 0 || hours > 0 || minutes > 0 {
+        parts.push(format!("{}m", minutes));
+    }
+    parts.push(format!("{}.{:03}s", rest / 1000, rest % 1000));
+    parts.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn packed(expiry: &Expiry) -> String {
+        String::from_utf8_lossy(&expiry.command(b"k").get_packed_command()).into_owned()
+    }
+
+    #[test]
+    fn parse_duration_units() {
+        assert_eq!(parse_duration("90").unwrap(), 90_000);
+        assert_eq!(parse_duration("500ms").unwrap(), 500);
+        assert_eq!(parse_duration("1h30m").unwrap(), 5_400_000);
+        assert_eq!(parse_duration("1w 2d").unwrap(), 777_600_000);
+        assert_eq!(parse_duration("1m1s1ms").unwrap(), 61_001);
+    }
+
+    #[test]
+    fn parse_duration_rejects_invalid() {
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("0").is_err());
+        assert!(parse_duration("0s").is_err());
+        assert!(parse_duration("-5").is_err());
+        assert!(parse_duration("h").is_err());
+        assert!(parse_duration("10x").is_err());
+    }
+
+    #[test]
+    fn parse_duration_rejects_overflow() {
+        assert!(parse_duration("10000000000000000").is_err());
+        assert!(parse_duration("99999999999999999999").is_err());
+        assert!(parse_duration("20000000000000000s").is_err());
+        assert!(parse_duration("9223372036854775000ms1s").is_err());
+        assert!(parse_duration("9223372036854775807ms").is_ok());
+        assert!(parse_duration("9223372036854775807ms1ms").is_err());
+    }
+
+    #[test]
+    fn parse_expiry_kinds() {
+        assert!(matches!(Expiry::parse("").unwrap(), Expiry::Persist));
+        assert!(matches!(Expiry::parse("-1").unwrap(), Expiry::Persist));
+        assert!(matches!(Expiry::parse("PERSIST").unwrap(), Expiry::Persist));
+        assert!(matches!(
+            Expiry::parse("2h").unwrap(),
+            Expiry::In(7_200_000)
+        ));
+        assert!(matches!(
+            Expiry::parse("@4102444800").unwrap(),
+            Expiry::At(4_102_444_800_000)
+        ));
+        assert!(matches!(
+            Expiry::parse("2100-01-02T03:04:05Z").unwrap(),
+            Expiry::At(4_102_542_245_000)
+        ));
+    }
+
+    #[test]
+    fn parse_expiry_rejects_out_of_range() {
+        assert!(Expiry::parse("@9000000000000000000").is_err());
+        assert!(Expiry::parse("@0").is_err());
+        assert!(Expiry::parse("@-5").is_err());
+        assert!(Expiry::parse("1960-01-01").is_err());
+    }
+
+    #[test]
+    fn parse_expiry_rejects_past_times() {
+        assert!(Expiry::parse("@1").is_err());
+        assert!(Expiry::parse("@1700000000").is_err());
+        assert!(Expiry::parse("2024-01-02 15:04").is_err());
+        assert!(Expiry::parse("2024-01-02T03:04:05Z").is_err());
+        let soon = Local::now() + chrono::Duration::hours(1);
+        let input = format!("@{}", soon.timestamp());
+        assert!(matches!(Expiry::parse(&input).unwrap(), Expiry::At(_)));
+        assert!(Expiry::parse("10000000000000000").is_err());
+    }
+
+    #[test]
+    fn command_uses_second_or_millisecond_precision() {
+        assert_eq!(
+            packed(&Expiry::In(90_000)),
+            "*3\r\n$6\r\nEXPIRE\r\n$1\r\nk\r\n$2\r\n90\r\n"
+        );
+        assert_eq!(
+            packed(&Expiry::In(1_500)),
+            "*3\r\n$7\r\nPEXPIRE\r\n$1\r\nk\r\n$4\r\n1500\r\n"
+        );
+        assert_eq!(
+            packed(&Expiry::At(1_700_000_000_000)),
+            "*3\r\n$8\r\nEXPIREAT\r\n$1\r\nk\r\n$10\r\n1700000000\r\n"
+        );
+        assert_eq!(
+            packed(&Expiry::Persist),
+            "*2\r\n$7\r\nPERSIST\r\n$1\r\nk\r\n"
+        );
+    }
+
+    #[test]
+    fn format_duration_parts() {
+        assert_eq!(format_duration(1_500), "1.500s");
+        assert_eq!(format_duration(3_661_000), "1h 1m 1.000s");
+        assert_eq!(format_duration(90_061_001), "1d 1h 1m 1.001s");
+    }
+}